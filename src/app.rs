@@ -0,0 +1,121 @@
+use crate::inspector::Section;
+use atspi::{
+    application::ApplicationProxy,
+    zbus::{self, names::BusName},
+};
+use gtk::prelude::*;
+use std::rc::Rc;
+
+/// What an application reports about itself, shared by all of its windows. Failed calls keep
+/// their error so the detail page can show it.
+#[derive(Debug)]
+pub struct AppInfo {
+    pub name: String,
+    pub bus_name: String,
+    pub toolkit: Result<String, String>,
+    pub toolkit_version: Result<String, String>,
+    pub atspi_version: Result<String, String>,
+    pub id: Result<i32, String>,
+    pub pid: Result<u32, String>,
+}
+
+impl AppInfo {
+    pub async fn load(conn: &zbus::Connection, bus_name: &str, name: String) -> Self {
+        let proxy = async {
+            ApplicationProxy::builder(conn)
+                .destination(bus_name.to_owned())?
+                .path("/org/a11y/atspi/accessible/root")?
+                .cache_properties(zbus::CacheProperties::No)
+                .build()
+                .await
+        }
+        .await;
+        let (toolkit, toolkit_version, atspi_version, id) = match proxy {
+            Ok(proxy) => (
+                proxy.toolkit_name().await.map_err(|e| e.to_string()),
+                proxy.version().await.map_err(|e| e.to_string()),
+                proxy.atspi_version().await.map_err(|e| e.to_string()),
+                proxy.id().await.map_err(|e| e.to_string()),
+            ),
+            Err(err) => {
+                let err = err.to_string();
+                (
+                    Err(err.clone()),
+                    Err(err.clone()),
+                    Err(err.clone()),
+                    Err(err),
+                )
+            }
+        };
+        let pid: zbus::Result<u32> = async {
+            let dbus = zbus::fdo::DBusProxy::new(conn).await?;
+            let name = BusName::try_from(bus_name)?;
+            Ok(dbus.get_connection_unix_process_id(name).await?)
+        }
+        .await;
+        Self {
+            name,
+            bus_name: bus_name.to_owned(),
+            toolkit,
+            toolkit_version,
+            atspi_version,
+            id,
+            pid: pid.map_err(|e| e.to_string()),
+        }
+    }
+    /// One line for the window list, e.g. `GTK 4.10.1 · pid 1234 · :1.42`.
+    pub fn subtitle(&self) -> String {
+        let mut parts = Vec::new();
+        match (&self.toolkit, &self.toolkit_version) {
+            (Ok(toolkit), Ok(version)) => parts.push(format!("{toolkit} {version}")),
+            (Ok(toolkit), Err(_)) => parts.push(toolkit.clone()),
+            _ => {}
+        }
+        if let Ok(pid) = self.pid {
+            parts.push(format!("pid {pid}"));
+        }
+        parts.push(self.bus_name.clone());
+        parts.join(" · ")
+    }
+}
+
+/// Sidebar page with the details of the application owning the inspected window.
+#[derive(Clone)]
+pub struct AppPage {
+    pub scroll: gtk::ScrolledWindow,
+    content: gtk::Box,
+}
+
+impl AppPage {
+    pub fn new() -> Self {
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .margin_top(8)
+            .margin_bottom(8)
+            .margin_start(8)
+            .margin_end(8)
+            .build();
+        let scroll = gtk::ScrolledWindow::builder()
+            .child(&content)
+            .vexpand(true)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .build();
+        Self { scroll, content }
+    }
+    pub fn set_app(&self, app: Option<Rc<AppInfo>>) {
+        while let Some(child) = self.content.first_child() {
+            self.content.remove(&child);
+        }
+        let Some(app) = app else {
+            return;
+        };
+        let section = Section::new(&app.name);
+        section.row("Bus name", &app.bus_name);
+        section.result("Toolkit", app.toolkit.as_ref());
+        section.result("Toolkit version", app.toolkit_version.as_ref());
+        section.result("AT-SPI version", app.atspi_version.as_ref());
+        section.result("Application ID", app.id.as_ref());
+        section.result("Process ID", app.pid.as_ref());
+        self.content.append(&section.widget);
+    }
+}
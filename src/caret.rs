@@ -0,0 +1,311 @@
+use crate::{
+    events::{Event, Subscription},
+    node::{Node, ObjectKey},
+    overview::{Mark, Overview},
+    tree::row_label,
+};
+use atspi::{text::TextProxy, zbus, CoordType};
+use futures_util::StreamExt;
+use gtk::{gdk, prelude::*};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+const TEXT_EVENTS: &[&str] = &[
+    "object:text-caret-moved",
+    "object:text-changed",
+    "object:text-selection-changed",
+];
+
+/// Events kept in the history list.
+const HISTORY_LEN: usize = 50;
+
+/// Upper bound on `GetSelection` calls for one event.
+const MAX_SELECTIONS: i32 = 100;
+
+/// Sidebar page following caret moves, selection changes and text changes in the inspected
+/// window, drawn live on the overview. Each event is checked against what the `Text` interface
+/// reports afterwards, so misplaced offsets stand out in the history.
+#[derive(Clone)]
+pub struct CaretPage {
+    pub widget: gtk::Box,
+    overview: Overview,
+    history: gtk::ListBox,
+    /// Source of each history row, newest first like the rows.
+    sources: Rc<RefCell<VecDeque<ObjectKey>>>,
+    handle: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+}
+
+impl CaretPage {
+    pub fn new(conn: &zbus::Connection, overview: &Overview) -> Self {
+        let history = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["navigation-sidebar"])
+            .build();
+        history.set_placeholder(Some(
+            &gtk::Label::builder()
+                .label("No text events yet")
+                .css_classes(["dim-label"])
+                .margin_top(12)
+                .build(),
+        ));
+        let page = Self {
+            widget: gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .build(),
+            overview: overview.clone(),
+            history,
+            sources: Default::default(),
+            handle: Default::default(),
+        };
+        {
+            let page = page.clone();
+            page.history.clone().connect_row_activated(move |_, row| {
+                let source = page.sources.borrow().get(row.index() as usize).cloned();
+                if let Some((dest, path)) = source {
+                    if !page.overview.pick_object(&dest, &path) {
+                        crate::toast(&page.widget, "Object is not loaded");
+                    }
+                }
+            });
+        }
+
+        let track = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        {
+            let page = page.clone();
+            let conn = conn.clone();
+            track.connect_active_notify(move |track| {
+                if let Some(handle) = page.handle.take() {
+                    handle.abort();
+                }
+                page.overview.clear_marks("caret:");
+                if track.is_active() {
+                    let fut = page.clone().track(conn.clone());
+                    page.handle
+                        .replace(Some(crate::spawn_fut(&page.widget, fut)));
+                }
+            });
+        }
+        let clear = gtk::Button::builder()
+            .icon_name("edit-clear-all-symbolic")
+            .tooltip_text("Clear")
+            .build();
+        {
+            let page = page.clone();
+            clear.connect_clicked(move |_| page.reset());
+        }
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(6)
+            .margin_end(6)
+            .build();
+        toolbar.append(
+            &gtk::Label::builder()
+                .label("Track caret")
+                .xalign(0.)
+                .hexpand(true)
+                .build(),
+        );
+        toolbar.append(&track);
+        toolbar.append(&clear);
+        page.widget.append(&toolbar);
+        page.widget.append(
+            &gtk::ScrolledWindow::builder()
+                .child(&page.history)
+                .vexpand(true)
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .build(),
+        );
+        page
+    }
+    /// Forgets the history and the overlay, e.g. when another window is inspected.
+    pub fn reset(&self) {
+        while let Some(row) = self.history.row_at_index(0) {
+            self.history.remove(&row);
+        }
+        self.sources.borrow_mut().clear();
+        self.overview.clear_marks("caret:");
+    }
+    async fn track(self, conn: zbus::Connection) -> anyhow::Result<()> {
+        let _sub = Subscription::new(&conn, TEXT_EVENTS).await?;
+        // text events come from every application, so only objects already loaded from the
+        // inspected window are queued, and the lookups happen behind the reader
+        let overview = self.overview.clone();
+        let mut events = crate::events::queued(&conn, move |event| {
+            matches!(
+                event.member.as_str(),
+                "TextCaretMoved" | "TextChanged" | "TextSelectionChanged"
+            ) && overview.is_loaded(&event.sender, &event.path)
+        });
+        while let Some(event) = events.next().await {
+            let Some(node) = self.overview.find(&event.sender, &event.path) else {
+                continue;
+            };
+            if let Err(err) = self.update(&node, &event).await {
+                log::debug!("{} on {}: {err}", event.name(), event.path.as_str());
+            }
+        }
+        Ok(())
+    }
+    /// Redraws the marks for one event and adds it to the history.
+    async fn update(&self, node: &Rc<RefCell<Node>>, event: &Event) -> zbus::Result<()> {
+        let proxy = node.borrow().proxy::<TextProxy<'static>>();
+        let proxy = proxy.await?;
+        // offsets come straight from the toolkit, so they are checked before being used
+        let count = proxy.character_count().await?;
+        let (summary, mismatch) = match event.member.as_str() {
+            "TextCaretMoved" => {
+                let offset = event.detail1;
+                let summary = format!("Caret moved to {offset}");
+                if !(0..=count).contains(&offset) {
+                    (summary, Some(out_of_range(count)))
+                } else {
+                    let rect = caret_rect(&proxy, offset).await?;
+                    self.overview.set_marks(
+                        "caret:caret",
+                        vec![Mark::new(rect, gdk::RGBA::new(0.9, 0.1, 0.1, 1.))],
+                    );
+                    let actual = proxy.caret_offset().await?;
+                    (
+                        summary,
+                        (actual != offset).then(|| format!("CaretOffset is {actual}")),
+                    )
+                }
+            }
+            "TextChanged" => {
+                let (start, length) = (event.detail1, event.detail2);
+                let end = start.saturating_add(length);
+                let text = String::try_from(event.any_data.clone()).unwrap_or_default();
+                let summary = format!("{} {length} at {start}: {text:?}", event.kind);
+                let inserted = event.kind.starts_with("insert");
+                // the deleted range is gone, so only its start is still in the text
+                let valid = if inserted {
+                    start >= 0 && length >= 0 && end <= count
+                } else {
+                    (0..=count).contains(&start) && length >= 0
+                };
+                if !valid {
+                    (summary, Some(out_of_range(count)))
+                } else {
+                    let (mark, mismatch) = if inserted {
+                        let (x, y, w, h) = proxy
+                            .get_range_extents(start, end, CoordType::Window)
+                            .await?;
+                        let actual = proxy.get_text(start, end).await?;
+                        (
+                            Mark::new(
+                                gdk::Rectangle::new(x, y, w, h),
+                                gdk::RGBA::new(1., 0.6, 0., 1.),
+                            )
+                            .filled(),
+                            (actual != text).then(|| format!("Text there is {actual:?}")),
+                        )
+                    } else {
+                        // mark where the deleted range was
+                        let rect = caret_rect(&proxy, start).await?;
+                        (Mark::new(rect, gdk::RGBA::new(1., 0.6, 0., 1.)), None)
+                    };
+                    self.overview.set_marks("caret:changed", vec![mark]);
+                    (summary, mismatch)
+                }
+            }
+            _ => {
+                let mut marks = Vec::new();
+                let mut ranges = Vec::new();
+                let n_selections = proxy.get_n_selections().await?;
+                for index in 0..n_selections.clamp(0, MAX_SELECTIONS) {
+                    let (start, end) = proxy.get_selection(index).await?;
+                    let (x, y, w, h) = proxy
+                        .get_range_extents(start, end, CoordType::Window)
+                        .await?;
+                    marks.push(
+                        Mark::new(
+                            gdk::Rectangle::new(x, y, w, h),
+                            gdk::RGBA::new(0.2, 0.5, 0.9, 1.),
+                        )
+                        .filled(),
+                    );
+                    ranges.push(format!("{start}–{end}"));
+                }
+                self.overview.set_marks("caret:selection", marks);
+                let mut summary = if ranges.is_empty() {
+                    "Selection cleared".to_owned()
+                } else {
+                    format!("Selected {}", ranges.join(", "))
+                };
+                if n_selections > MAX_SELECTIONS {
+                    summary.push_str(&format!(" (first {MAX_SELECTIONS} of {n_selections})"));
+                }
+                (summary, None)
+            }
+        };
+        let source = row_label(&node.borrow());
+        self.push(event, &summary, &source, mismatch);
+        Ok(())
+    }
+    fn push(&self, event: &Event, summary: &str, source: &str, mismatch: Option<String>) {
+        let vbox = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(2)
+            .margin_top(2)
+            .margin_bottom(2)
+            .build();
+        vbox.append(
+            &gtk::Label::builder()
+                .label(summary)
+                .xalign(0.)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .build(),
+        );
+        vbox.append(
+            &gtk::Label::builder()
+                .label(format!(
+                    "{} · {source}",
+                    crate::monitor::format_time(event.time)
+                ))
+                .xalign(0.)
+                .ellipsize(gtk::pango::EllipsizeMode::Middle)
+                .css_classes(["caption", "dim-label"])
+                .build(),
+        );
+        if let Some(mismatch) = mismatch {
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(mismatch)
+                    .xalign(0.)
+                    .wrap(true)
+                    .css_classes(["warning", "caption"])
+                    .build(),
+            );
+        }
+        self.history.prepend(&vbox);
+        let mut sources = self.sources.borrow_mut();
+        sources.push_front((event.sender.clone(), event.path.clone()));
+        if sources.len() > HISTORY_LEN {
+            sources.truncate(HISTORY_LEN);
+            if let Some(row) = self.history.row_at_index(HISTORY_LEN as i32) {
+                self.history.remove(&row);
+            }
+        }
+    }
+}
+
+/// A zero-width rectangle at the caret position before `offset`.
+async fn caret_rect(proxy: &TextProxy<'static>, offset: i32) -> zbus::Result<gdk::Rectangle> {
+    let (x, y, _, h) = proxy
+        .get_character_extents(offset, CoordType::Window)
+        .await?;
+    if h > 0 || offset == 0 {
+        return Ok(gdk::Rectangle::new(x, y, 0, h));
+    }
+    // past the last character there is nothing to measure, so use the end of the one before
+    let (x, y, w, h) = proxy
+        .get_character_extents(offset.saturating_sub(1), CoordType::Window)
+        .await?;
+    Ok(gdk::Rectangle::new(x + w, y, 0, h))
+}
+
+fn out_of_range(count: i32) -> String {
+    format!("Offset is outside the text, which has {count} characters")
+}
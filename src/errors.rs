@@ -0,0 +1,73 @@
+use crate::{node::Node, tree::row_label};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Lists nodes whose calls failed during loading, with the object paths needed for bug reports.
+#[derive(Clone)]
+pub struct ErrorList {
+    pub scroll: gtk::ScrolledWindow,
+    list: gtk::ListView,
+}
+
+impl ErrorList {
+    pub fn new(errors: &gio::ListStore) -> Self {
+        let select = gtk::NoSelection::new(Some(errors.clone()));
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_bind(|_, obj| {
+            let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+            let node = Node::from_item(&item.item().unwrap());
+            let node = node.borrow();
+            let vbox = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(2)
+                .margin_top(4)
+                .margin_bottom(4)
+                .build();
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(row_label(&node))
+                    .xalign(0.)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .css_classes(["heading"])
+                    .build(),
+            );
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(format!("{} {}", node.dest, node.path.as_str()))
+                    .xalign(0.)
+                    .selectable(true)
+                    .wrap(true)
+                    .wrap_mode(gtk::pango::WrapMode::WordChar)
+                    .css_classes(["monospace", "caption"])
+                    .build(),
+            );
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(node.error.as_deref().unwrap_or_default())
+                    .xalign(0.)
+                    .selectable(true)
+                    .wrap(true)
+                    .wrap_mode(gtk::pango::WrapMode::WordChar)
+                    .css_classes(["warning", "caption"])
+                    .build(),
+            );
+            item.set_child(Some(&vbox));
+        });
+        let list = gtk::ListView::new(Some(select), Some(factory));
+        list.set_single_click_activate(true);
+        let scroll = gtk::ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .build();
+        Self { scroll, list }
+    }
+    pub fn connect_activated<F: Fn(Rc<RefCell<Node>>) + 'static>(&self, f: F) {
+        self.list.connect_activate(move |list, pos| {
+            let model = list.model().unwrap();
+            if let Some(obj) = model.item(pos) {
+                f(Node::from_item(&obj));
+            }
+        });
+    }
+}
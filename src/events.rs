@@ -0,0 +1,307 @@
+use atspi::zbus::{
+    self,
+    zvariant::{OwnedObjectPath, OwnedValue},
+};
+use futures_channel::mpsc;
+use futures_util::{stream::LocalBoxStream, StreamExt};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::SystemTime,
+};
+
+const EVENT_INTERFACE: &str = "org.a11y.atspi.Event.";
+
+/// A signal emitted on one of the `org.a11y.atspi.Event.*` interfaces.
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// Interface suffix, e.g. `Object`.
+    pub class: String,
+    /// Signal name, e.g. `ChildrenChanged`.
+    pub member: String,
+    /// Event detail, e.g. `add` or `focused`.
+    pub kind: String,
+    pub detail1: i32,
+    pub detail2: i32,
+    pub any_data: OwnedValue,
+    pub sender: String,
+    pub path: OwnedObjectPath,
+    /// When the event was received.
+    pub time: SystemTime,
+}
+
+impl Event {
+    fn from_message(msg: &zbus::Message) -> Option<Self> {
+        if msg.message_type() != zbus::MessageType::Signal {
+            return None;
+        }
+        let class = msg
+            .interface()?
+            .as_str()
+            .strip_prefix(EVENT_INTERFACE)?
+            .to_owned();
+        let member = msg.member()?.to_string();
+        let path = msg.path()?.into();
+        let sender = msg.header().ok()?.sender().ok()??.to_string();
+        // Qt sends a `(so)` where everyone else sends a property dict
+        let (kind, detail1, detail2, any_data) =
+            match msg.body::<(String, i32, i32, OwnedValue, HashMap<String, OwnedValue>)>() {
+                Ok((kind, detail1, detail2, any_data, _)) => (kind, detail1, detail2, any_data),
+                Err(_) => {
+                    let (kind, detail1, detail2, any_data, _) = msg
+                        .body::<(String, i32, i32, OwnedValue, (String, OwnedObjectPath))>()
+                        .ok()?;
+                    (kind, detail1, detail2, any_data)
+                }
+            };
+        Some(Self {
+            class,
+            member,
+            kind,
+            detail1,
+            detail2,
+            any_data,
+            sender,
+            path,
+            time: SystemTime::now(),
+        })
+    }
+    /// Registry-style name, e.g. `object:state-changed:focused`.
+    pub fn name(&self) -> String {
+        let mut name = format!("{}:{}", kebab(&self.class), kebab(&self.member));
+        if !self.kind.is_empty() {
+            name.push(':');
+            name.push_str(&self.kind);
+        }
+        name
+    }
+    /// `any_data` as shown to people: plain values unwrapped, anything else debug-printed.
+    pub fn any_data_text(&self) -> String {
+        use zbus::zvariant::Value;
+        match &*self.any_data {
+            Value::Str(s) => s.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::I32(i) => i.to_string(),
+            Value::U32(u) => u.to_string(),
+            Value::I64(i) => i.to_string(),
+            Value::F64(f) => f.to_string(),
+            Value::ObjectPath(p) => p.to_string(),
+            Value::Structure(s) => match s.fields() {
+                [Value::Str(dest), Value::ObjectPath(path)] => format!("{dest} {path}"),
+                fields => format!("{fields:?}"),
+            },
+            Value::Value(v) => format!("{v:?}"),
+            v => format!("{v:?}"),
+        }
+    }
+}
+
+thread_local! {
+    /// Live subscriptions per event name, so overlapping ones share a single registry entry and
+    /// dropping one does not deregister an event another still needs.
+    static REGISTERED: RefCell<HashMap<String, usize>> = Default::default();
+}
+
+/// Keeps events registered with the registry until dropped.
+pub struct Subscription {
+    conn: zbus::Connection,
+    events: Vec<String>,
+}
+
+impl Subscription {
+    /// Registers registry-style event names such as `object:children-changed`, or `window:` for a
+    /// whole class.
+    pub async fn new(conn: &zbus::Connection, events: &[&str]) -> zbus::Result<Self> {
+        let registry = atspi::registry::RegistryProxy::new(conn).await?;
+        let dbus = zbus::fdo::DBusProxy::new(conn).await?;
+        let mut sub = Self {
+            conn: conn.clone(),
+            events: Vec::new(),
+        };
+        for event in events {
+            let rule = zbus::MatchRule::try_from(match_rule(event).as_str())?.into_owned();
+            dbus.add_match_rule(rule.clone()).await?;
+            // only counted once registered, so a failure leaves nothing for drop to undo
+            if !is_registered(event) {
+                if let Err(err) = registry.register_event(event).await {
+                    dbus.remove_match_rule(rule).await.ok();
+                    return Err(err);
+                }
+            }
+            REGISTERED.with(|registered| {
+                *registered
+                    .borrow_mut()
+                    .entry(event.to_string())
+                    .or_default() += 1;
+            });
+            sub.events.push(event.to_string());
+        }
+        Ok(sub)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let conn = self.conn.clone();
+        let events = std::mem::take(&mut self.events);
+        let mut last = Vec::<String>::new();
+        REGISTERED.with(|registered| {
+            let mut registered = registered.borrow_mut();
+            for event in &events {
+                if let Some(count) = registered.get_mut(event) {
+                    *count -= 1;
+                    if *count == 0 {
+                        registered.remove(event);
+                        last.push(event.clone());
+                    }
+                }
+            }
+        });
+        glib::MainContext::default().spawn_local(async move {
+            let res: zbus::Result<()> = async {
+                let registry = atspi::registry::RegistryProxy::new(&conn).await?;
+                let dbus = zbus::fdo::DBusProxy::new(&conn).await?;
+                // one may have been subscribed again in the meantime
+                last.retain(|event| !is_registered(event));
+                for event in &last {
+                    registry.deregister_event(event).await?;
+                }
+                // the registry drops every event of the class on `object:` and the like, so
+                // put back what other subscriptions still need
+                let classes = last.iter().map(|event| class(event)).collect::<Vec<_>>();
+                let survivors = REGISTERED.with(|registered| {
+                    registered
+                        .borrow()
+                        .keys()
+                        .filter(|event| classes.contains(&class(event)))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                });
+                for event in &survivors {
+                    registry.register_event(event).await?;
+                }
+                for event in &events {
+                    let rule = match_rule(event);
+                    dbus.remove_match_rule(zbus::MatchRule::try_from(rule.as_str())?)
+                        .await?;
+                }
+                Ok(())
+            }
+            .await;
+            if let Err(err) = res {
+                log::warn!("Failed to deregister events: {err}");
+            }
+        });
+    }
+}
+
+/// All AT-SPI events arriving on `conn`, whether or not this stream's owner registered them.
+pub fn stream(conn: &zbus::Connection) -> LocalBoxStream<'static, Event> {
+    zbus::MessageStream::from(conn)
+        .filter_map(|msg| async move { msg.ok().and_then(|msg| Event::from_message(&msg)) })
+        .boxed_local()
+}
+
+/// Events matching `filter`, read off the connection by a task of their own and queued without
+/// bound, so the consumer can await calls between events. A `stream` that is not polled stops
+/// zbus from reading the connection once its queue is full, and the reply being awaited then never
+/// arrives. The reader stops at the first event after the receiver is dropped.
+pub fn queued(
+    conn: &zbus::Connection,
+    filter: impl Fn(&Event) -> bool + 'static,
+) -> mpsc::UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded();
+    let mut events = stream(conn);
+    glib::MainContext::default().spawn_local(async move {
+        while let Some(event) = events.next().await {
+            if !filter(&event) {
+                continue;
+            }
+            if tx.unbounded_send(event).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Like `queued`, but holding at most about `len` events, for consumers that may fall behind a
+/// flood. Events arriving while it is full are dropped and counted in `dropped`.
+pub fn bounded(
+    conn: &zbus::Connection,
+    len: usize,
+    dropped: Rc<Cell<usize>>,
+    filter: impl Fn(&Event) -> bool + 'static,
+) -> mpsc::Receiver<Event> {
+    let (mut tx, rx) = mpsc::channel(len);
+    let mut events = stream(conn);
+    glib::MainContext::default().spawn_local(async move {
+        while let Some(event) = events.next().await {
+            if !filter(&event) {
+                continue;
+            }
+            match tx.try_send(event) {
+                Ok(()) => {}
+                Err(err) if err.is_full() => dropped.set(dropped.get() + 1),
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+/// The newest of `event` and whatever was queued behind it, for consumers that only care about
+/// the latest state.
+pub fn latest(events: &mut mpsc::UnboundedReceiver<Event>, mut event: Event) -> Event {
+    while let Ok(Some(next)) = events.try_next() {
+        event = next;
+    }
+    event
+}
+
+fn is_registered(event: &str) -> bool {
+    REGISTERED.with(|registered| registered.borrow().contains_key(event))
+}
+
+/// The class part of a registry-style name, e.g. `object` for `object:state-changed:focused`.
+fn class(event: &str) -> &str {
+    event.split(':').next().unwrap_or_default()
+}
+
+fn match_rule(event: &str) -> String {
+    let mut parts = event.split(':');
+    let class = camel(parts.next().unwrap_or_default());
+    let mut rule = format!("type='signal',interface='{EVENT_INTERFACE}{class}'");
+    if let Some(member) = parts.next().filter(|m| !m.is_empty()) {
+        rule.push_str(&format!(",member='{}'", camel(member)));
+    }
+    rule
+}
+
+fn camel(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn kebab(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('-');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
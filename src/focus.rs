@@ -0,0 +1,100 @@
+use crate::{events::Subscription, node::ObjectKey, overview::Overview, Root};
+use atspi::{accessible::AccessibleProxy, zbus};
+use futures_util::StreamExt;
+use gtk::prelude::*;
+
+const FOCUS_EVENTS: &[&str] = &["object:state-changed:focused", "focus:"];
+
+const APP_ROOT: &str = "/org/a11y/atspi/accessible/root";
+
+/// Gives up on parent chains longer than this, in case they loop.
+const MAX_DEPTH: usize = 256;
+
+/// Follows focus across applications: selects the window owning each newly focused object in
+/// `windows`, which loads it into `overview`, then picks the object there. Focus moving within
+/// this process is ignored, or using the inspector would inspect the inspector.
+pub async fn follow(
+    conn: zbus::Connection,
+    windows: gtk::SingleSelection,
+    overview: Overview,
+) -> anyhow::Result<()> {
+    let _sub = Subscription::new(&conn, FOCUS_EVENTS).await?;
+    let own = windows.clone();
+    let mut events = crate::events::queued(&conn, move |event| {
+        let focused = match (event.class.as_str(), event.member.as_str()) {
+            ("Focus", _) => true,
+            ("Object", "StateChanged") => event.kind == "focused" && event.detail1 == 1,
+            _ => false,
+        };
+        focused && !is_own(&own, &event.sender)
+    });
+    while let Some(event) = events.next().await {
+        // only where focus ended up matters after a burst of changes
+        let event = crate::events::latest(&mut events, event);
+        let window = match window_of(&conn, &event.sender, &event.path).await {
+            Ok(Some(window)) => window,
+            Ok(None) => continue,
+            Err(err) => {
+                log::debug!("No window for {}: {err}", event.path.as_str());
+                continue;
+            }
+        };
+        let Some(index) = position(&windows, &window) else {
+            log::debug!("{} is not in the window list", window.1.as_str());
+            continue;
+        };
+        if windows.selected() != index {
+            windows.set_selected(index);
+        }
+        overview.pick_when_loaded(&event.sender, &event.path);
+    }
+    Ok(())
+}
+
+/// The top-level object under the application root that contains the object.
+async fn window_of(
+    conn: &zbus::Connection,
+    dest: &str,
+    path: &str,
+) -> zbus::Result<Option<ObjectKey>> {
+    let mut current: ObjectKey = (dest.to_owned(), path.try_into()?);
+    for _ in 0..MAX_DEPTH {
+        if current.1.as_str() == APP_ROOT {
+            return Ok(None);
+        }
+        let proxy = AccessibleProxy::builder(conn)
+            .destination(current.0.clone())?
+            .path(current.1.clone())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let parent = proxy.parent().await?;
+        if parent.1.as_str() == APP_ROOT {
+            return Ok(Some(current));
+        }
+        current = parent;
+    }
+    Ok(None)
+}
+
+/// Whether `sender` is an application listed in `windows` running in this process.
+fn is_own(windows: &gtk::SingleSelection, sender: &str) -> bool {
+    let pid = std::process::id();
+    (0..windows.n_items()).any(|index| {
+        let Some(obj) = windows.item(index).and_downcast::<glib::BoxedAnyObject>() else {
+            return false;
+        };
+        let root = obj.borrow::<Root>();
+        root.proxy.destination().as_str() == sender && root.app.pid.as_ref() == Ok(&pid)
+    })
+}
+
+fn position(windows: &gtk::SingleSelection, (dest, path): &ObjectKey) -> Option<u32> {
+    (0..windows.n_items()).find(|&index| {
+        let Some(obj) = windows.item(index).and_downcast::<glib::BoxedAnyObject>() else {
+            return false;
+        };
+        let root = obj.borrow::<Root>();
+        root.proxy.destination().as_str() == dest && root.proxy.path().as_str() == path.as_str()
+    })
+}
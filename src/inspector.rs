@@ -0,0 +1,475 @@
+use crate::{
+    node::{Node, ObjectKey},
+    overview::{Mark, Overview},
+    tree::row_label,
+};
+use atspi::{
+    component::ComponentProxy,
+    zbus::{self, zvariant::OwnedObjectPath},
+    CoordType,
+};
+use gtk::{gdk, prelude::*};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+    rc::Rc,
+};
+
+mod action;
+mod component;
+mod document;
+mod editable;
+mod hypertext;
+mod image;
+mod selection;
+mod table;
+mod text;
+mod value;
+
+/// Docked pane listing everything known about the picked node. Values are fetched from the
+/// application whenever the node is picked or changes, and every value can be selected and copied.
+#[derive(Clone)]
+pub struct Inspector {
+    pub scroll: gtk::ScrolledWindow,
+    overview: Overview,
+    title: gtk::Label,
+    /// Sections read through `Accessible`, rebuilt whenever the node changes.
+    general: gtk::Box,
+    /// Sections for the other interfaces, which hold input and listeners and are only rebuilt
+    /// when the node is picked or its interfaces change.
+    details: gtk::Box,
+    node: Rc<RefCell<Option<Rc<RefCell<Node>>>>>,
+    /// Interfaces the shown `details` were built for.
+    interfaces: Rc<Cell<Option<atspi::InterfaceSet>>>,
+    handle: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+    details_handle: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+    /// Listeners started by sections, stopped when the pane is reloaded.
+    tasks: Rc<RefCell<Vec<glib::JoinHandle<()>>>>,
+}
+
+impl Inspector {
+    pub fn new(overview: &Overview) -> Self {
+        let title = gtk::Label::builder()
+            .label("Nothing picked")
+            .xalign(0.)
+            .wrap(true)
+            .wrap_mode(gtk::pango::WrapMode::WordChar)
+            .selectable(true)
+            .css_classes(["title-4"])
+            .build();
+        let general = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+        let details = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+        let vbox = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .margin_top(8)
+            .margin_bottom(8)
+            .margin_start(8)
+            .margin_end(8)
+            .build();
+        vbox.append(&title);
+        vbox.append(&general);
+        vbox.append(&details);
+        let scroll = gtk::ScrolledWindow::builder()
+            .child(&vbox)
+            .vexpand(true)
+            .width_request(260)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .build();
+        Self {
+            scroll,
+            overview: overview.clone(),
+            title,
+            general,
+            details,
+            node: Default::default(),
+            interfaces: Default::default(),
+            handle: Default::default(),
+            details_handle: Default::default(),
+            tasks: Default::default(),
+        }
+    }
+    pub fn node(&self) -> Option<Rc<RefCell<Node>>> {
+        self.node.borrow().clone()
+    }
+    /// Shows `node`, or an empty pane for `None`. Picking the node already shown does nothing.
+    pub fn set_node(&self, node: Option<Rc<RefCell<Node>>>) {
+        let same = match (&*self.node.borrow(), &node) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        if same {
+            return;
+        }
+        self.node.replace(node);
+        self.reload();
+    }
+    /// Updates the pane if `node` is the one shown. Only the general sections are fetched again
+    /// unless the interfaces changed, so input, selections and scroll positions in the others
+    /// survive.
+    pub fn refresh(&self, node: &Rc<RefCell<Node>>) {
+        let shown = self
+            .node
+            .borrow()
+            .as_ref()
+            .map_or(false, |n| Rc::ptr_eq(n, node));
+        if !shown {
+            return;
+        }
+        if self.interfaces.get() != Some(node.borrow().interfaces) {
+            self.reload();
+            return;
+        }
+        self.title.set_label(&row_label(&node.borrow()));
+        self.load_general(node);
+    }
+    fn reload(&self) {
+        if let Some(handle) = self.details_handle.take() {
+            handle.abort();
+        }
+        for task in self.tasks.take() {
+            task.abort();
+        }
+        self.overview.clear_marks("inspector:");
+        for section in [&self.general, &self.details] {
+            while let Some(child) = section.first_child() {
+                section.remove(&child);
+            }
+        }
+        let Some(node) = self.node() else {
+            if let Some(handle) = self.handle.take() {
+                handle.abort();
+            }
+            self.interfaces.set(None);
+            self.title.set_label("Nothing picked");
+            return;
+        };
+        self.interfaces.set(Some(node.borrow().interfaces));
+        self.overview.set_relations(&node, Vec::new());
+        self.title.set_label(&row_label(&node.borrow()));
+        self.load_general(&node);
+        let inspector = self.clone();
+        let handle = crate::spawn_fut(&self.scroll, async move {
+            for section in interface_sections(&inspector, &node).await {
+                inspector.details.append(&section.widget);
+            }
+            inspector.details_handle.take();
+            Ok(())
+        });
+        self.details_handle.replace(Some(handle));
+    }
+    /// Replaces the general sections once all of them are fetched, so they do not flicker.
+    fn load_general(&self, node: &Rc<RefCell<Node>>) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+        let inspector = self.clone();
+        let node = node.clone();
+        let handle = crate::spawn_fut(&self.scroll, async move {
+            let sections = general_sections(&inspector, &node).await?;
+            while let Some(child) = inspector.general.first_child() {
+                inspector.general.remove(&child);
+            }
+            for section in sections {
+                inspector.general.append(&section.widget);
+            }
+            inspector.handle.take();
+            Ok(())
+        });
+        self.handle.replace(Some(handle));
+    }
+    /// Runs `fut` until the pane shows something else.
+    fn spawn_task(&self, fut: impl std::future::Future<Output = anyhow::Result<()>> + 'static) {
+        let handle = crate::spawn_fut(&self.scroll, fut);
+        self.tasks.borrow_mut().push(handle);
+    }
+    /// Outlines objects on the canvas, whether or not they were loaded, replacing `group`.
+    fn mark_objects(&self, group: &str, objects: Vec<ObjectKey>, color: gdk::RGBA) {
+        let Some(node) = self.node() else {
+            return;
+        };
+        let conn = node.borrow().conn.clone();
+        let overview = self.overview.clone();
+        let group = group.to_owned();
+        glib::MainContext::default().spawn_local(async move {
+            let mut marks = Vec::new();
+            for (dest, path) in objects {
+                let extents = match overview.find(&dest, &path) {
+                    Some(node) => Ok(node.borrow().extents),
+                    None => object_extents(&conn, dest, path.clone()).await,
+                };
+                match extents {
+                    Ok(rect) => marks.push(Mark::new(rect, color).filled()),
+                    Err(err) => log::debug!("GetExtents on {}: {err}", path.as_str()),
+                }
+            }
+            overview.set_marks(&group, marks);
+        });
+    }
+    /// Jumps to the object when clicked, if it was loaded.
+    fn target_button(&self, dest: &str, path: &OwnedObjectPath) -> gtk::Button {
+        let label = match self.overview.find(dest, path) {
+            Some(target) => row_label(&target.borrow()),
+            None => format!("{dest} {}", path.as_str()),
+        };
+        let button = gtk::Button::builder()
+            .child(
+                &gtk::Label::builder()
+                    .label(label)
+                    .xalign(0.)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build(),
+            )
+            .css_classes(["flat"])
+            .build();
+        let overview = self.overview.clone();
+        let (dest, path) = (dest.to_owned(), path.clone());
+        button.connect_clicked(move |button| {
+            if !overview.pick_object(&dest, &path) {
+                crate::toast(button, &format!("{} is not loaded", path.as_str()));
+            }
+        });
+        button
+    }
+}
+
+async fn object_extents(
+    conn: &zbus::Connection,
+    dest: String,
+    path: OwnedObjectPath,
+) -> zbus::Result<gdk::Rectangle> {
+    let component = ComponentProxy::builder(conn)
+        .destination(dest)?
+        .path(path)?
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await?;
+    let (x, y, w, h) = component.get_extents(CoordType::Window).await?;
+    Ok(gdk::Rectangle::new(x, y, w, h))
+}
+
+async fn general_sections(
+    inspector: &Inspector,
+    node: &Rc<RefCell<Node>>,
+) -> anyhow::Result<Vec<Section>> {
+    let proxy = node.borrow().accessible();
+    let proxy = proxy.await?;
+    let mut sections = Vec::new();
+
+    let props = Section::new("Properties");
+    {
+        let node = node.borrow();
+        props.row("Name", &node.name);
+        props.row("Role", node.role.name());
+        props.row("Object", &format!("{} {}", node.dest, node.path.as_str()));
+    }
+    props.result("Description", proxy.description().await);
+    // `AccessibleProxy` in atspi 0.15 has no `HelpText` accessor
+    props.result(
+        "Help text",
+        proxy.inner().get_property::<String>("HelpText").await,
+    );
+    props.result("Locale", proxy.locale().await);
+    props.result("Accessible ID", proxy.accessible_id().await);
+    props.result("Index in parent", proxy.get_index_in_parent().await);
+    props.result("Child count", proxy.child_count().await);
+    sections.push(props);
+
+    let ifaces = Section::new("Interfaces");
+    match proxy.get_interfaces().await {
+        Ok(set) => ifaces.list(set.iter().map(|iface| format!("{iface:?}"))),
+        Err(err) => ifaces.error(&err),
+    }
+    sections.push(ifaces);
+
+    let states = Section::new("States");
+    match proxy.get_state().await {
+        Ok(set) => states.list(set.iter().map(|state| format!("{state:?}"))),
+        Err(err) => states.error(&err),
+    }
+    sections.push(states);
+
+    let attrs = Section::new("Attributes");
+    match proxy.get_attributes().await {
+        Ok(map) if map.is_empty() => attrs.empty(),
+        Ok(map) => {
+            let mut map = map.into_iter().collect::<Vec<_>>();
+            map.sort();
+            for (key, value) in map {
+                attrs.row(&key, &value);
+            }
+        }
+        Err(err) => attrs.error(&err),
+    }
+    sections.push(attrs);
+
+    let relations = Section::new("Relations");
+    match proxy.get_relation_set().await {
+        Ok(set) if set.is_empty() => relations.empty(),
+        Ok(set) => {
+            for (kind, targets) in &set {
+                let targets_box = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .build();
+                for (dest, path) in targets {
+                    targets_box.append(&inspector.target_button(dest, path));
+                }
+                relations.row_widget(&format!("{kind:?}"), &targets_box);
+            }
+            inspector.overview.set_relations(node, set);
+        }
+        Err(err) => relations.error(&err),
+    }
+    sections.push(relations);
+    Ok(sections)
+}
+
+async fn interface_sections(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let ifaces = node.borrow().interfaces;
+    if ifaces.contains(atspi::Interface::Action) {
+        sections.push(action::section(node).await);
+    }
+    if ifaces.contains(atspi::Interface::Text) {
+        sections.push(text::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::Value) {
+        sections.push(value::section(node).await);
+    }
+    if ifaces.contains(atspi::Interface::Component) {
+        sections.push(component::section(node).await);
+    }
+    if ifaces.contains(atspi::Interface::Document) {
+        sections.push(document::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::EditableText) {
+        sections.push(editable::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::Hypertext) {
+        sections.push(hypertext::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::Image) {
+        sections.push(image::section(node).await);
+    }
+    if ifaces.contains(atspi::Interface::Selection) {
+        sections.push(selection::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::Table) {
+        sections.push(table::section(inspector, node).await);
+    }
+    if ifaces.contains(atspi::Interface::TableCell) {
+        sections.push(table::cell_section(inspector, node).await);
+    }
+    sections
+}
+
+/// A titled block of `label: value` rows.
+pub struct Section {
+    pub widget: gtk::Box,
+    grid: gtk::Grid,
+    rows: std::cell::Cell<i32>,
+}
+
+impl Section {
+    pub fn new(title: &str) -> Self {
+        let widget = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .build();
+        widget.append(
+            &gtk::Label::builder()
+                .label(title)
+                .xalign(0.)
+                .css_classes(["heading"])
+                .build(),
+        );
+        let grid = gtk::Grid::builder()
+            .row_spacing(4)
+            .column_spacing(8)
+            .build();
+        widget.append(&grid);
+        Self {
+            widget,
+            grid,
+            rows: Default::default(),
+        }
+    }
+    /// Adds a row and returns its value label.
+    pub fn row(&self, label: &str, value: &str) -> gtk::Label {
+        let row = self.rows.get();
+        self.rows.set(row + 1);
+        self.grid.attach(&key_label(label), 0, row, 1, 1);
+        let value = value_label(value);
+        self.grid.attach(&value, 1, row, 1, 1);
+        value
+    }
+    /// Adds a row with an arbitrary value widget.
+    pub fn row_widget(&self, label: &str, value: &impl IsA<gtk::Widget>) {
+        let row = self.rows.get();
+        self.rows.set(row + 1);
+        self.grid.attach(&key_label(label), 0, row, 1, 1);
+        self.grid.attach(value, 1, row, 1, 1);
+    }
+    /// Adds a row showing either the value or the error that replaced it.
+    pub fn result<T: Display, E: Display>(&self, label: &str, res: Result<T, E>) -> gtk::Label {
+        match res {
+            Ok(value) => self.row(label, &value.to_string()),
+            Err(err) => {
+                let value = self.row(label, &err.to_string());
+                value.add_css_class("warning");
+                value
+            }
+        }
+    }
+    /// Adds a widget spanning both columns.
+    pub fn append(&self, widget: &impl IsA<gtk::Widget>) {
+        let row = self.rows.get();
+        self.rows.set(row + 1);
+        self.grid.attach(widget, 0, row, 2, 1);
+    }
+    pub fn list(&self, items: impl Iterator<Item = String>) {
+        let items = items.collect::<Vec<_>>();
+        if items.is_empty() {
+            self.empty();
+        } else {
+            self.append(&value_label(&items.join("\n")));
+        }
+    }
+    pub fn empty(&self) {
+        let label = value_label("None");
+        label.add_css_class("dim-label");
+        self.append(&label);
+    }
+    pub fn error(&self, err: &dyn Display) {
+        let label = value_label(&err.to_string());
+        label.add_css_class("warning");
+        self.append(&label);
+    }
+}
+
+fn key_label(label: &str) -> gtk::Label {
+    gtk::Label::builder()
+        .label(label)
+        .xalign(1.)
+        .yalign(0.)
+        .css_classes(["dim-label"])
+        .build()
+}
+
+fn value_label(value: &str) -> gtk::Label {
+    gtk::Label::builder()
+        .label(value)
+        .xalign(0.)
+        .hexpand(true)
+        .selectable(true)
+        .wrap(true)
+        .wrap_mode(gtk::pango::WrapMode::WordChar)
+        .build()
+}
@@ -0,0 +1,66 @@
+use super::Section;
+use crate::node::Node;
+use atspi::{action::ActionProxy, zbus};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+pub async fn section(node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Actions");
+    if let Err(err) = fill(&section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(section: &Section, node: &Rc<RefCell<Node>>) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<ActionProxy<'static>>();
+    let proxy = proxy.await?;
+    let count = proxy.n_actions().await?;
+    if count <= 0 {
+        section.empty();
+    }
+    for index in 0..count {
+        let name = proxy.get_name(index).await?;
+        let grid = gtk::Grid::builder()
+            .row_spacing(2)
+            .column_spacing(8)
+            .margin_bottom(6)
+            .build();
+        let rows = [
+            ("Localized name", proxy.get_localized_name(index).await),
+            ("Description", proxy.get_description(index).await),
+            ("Key binding", proxy.get_key_binding(index).await),
+        ];
+        for (row, (label, value)) in rows.into_iter().enumerate() {
+            let value = match value {
+                Ok(value) => super::value_label(&value),
+                Err(err) => {
+                    let value = super::value_label(&err.to_string());
+                    value.add_css_class("warning");
+                    value
+                }
+            };
+            grid.attach(&super::key_label(label), 0, row as i32, 1, 1);
+            grid.attach(&value, 1, row as i32, 1, 1);
+        }
+        let button = gtk::Button::builder()
+            .label(if name.is_empty() { "(unnamed)" } else { &name })
+            .tooltip_text("DoAction")
+            .halign(gtk::Align::Start)
+            .build();
+        let proxy = proxy.clone();
+        button.connect_clicked(move |button| {
+            let proxy = proxy.clone();
+            let b = button.clone();
+            crate::spawn_fut(button, async move {
+                if !proxy.do_action(index).await? {
+                    crate::toast(&b, "The application refused the action");
+                }
+                Ok(())
+            });
+        });
+        section.append(&button);
+        section.append(&grid);
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use super::Section;
+use crate::node::Node;
+use atspi::{component::ComponentProxy, zbus, CoordType, ScrollType};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+const SCROLL_TYPES: &[(ScrollType, &str)] = &[
+    (ScrollType::TopLeft, "Top left"),
+    (ScrollType::BottomRight, "Bottom right"),
+    (ScrollType::TopEdge, "Top edge"),
+    (ScrollType::BottomEdge, "Bottom edge"),
+    (ScrollType::LeftEdge, "Left edge"),
+    (ScrollType::RightEdge, "Right edge"),
+    (ScrollType::Anywhere, "Anywhere"),
+];
+
+pub async fn section(node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Component");
+    if let Err(err) = fill(&section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(section: &Section, node: &Rc<RefCell<Node>>) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<ComponentProxy<'static>>();
+    let proxy = proxy.await?;
+    let rect = |(x, y, w, h): (i32, i32, i32, i32)| format!("{w}×{h} at {x}, {y}");
+    section.result(
+        "Extents",
+        proxy.get_extents(CoordType::Window).await.map(rect),
+    );
+    section.result(
+        "Screen extents",
+        proxy.get_extents(CoordType::Screen).await.map(rect),
+    );
+    section.result(
+        "Layer",
+        proxy.get_layer().await.map(|layer| format!("{layer:?}")),
+    );
+    section.result("MDI z-order", proxy.get_mdiz_order().await);
+    section.result("Alpha", proxy.get_alpha().await);
+
+    let focus = gtk::Button::builder()
+        .label("Grab focus")
+        .halign(gtk::Align::Start)
+        .build();
+    {
+        let proxy = proxy.clone();
+        focus.connect_clicked(move |button| {
+            let proxy = proxy.clone();
+            let b = button.clone();
+            crate::spawn_fut(button, async move {
+                if !proxy.grab_focus().await? {
+                    crate::toast(&b, "GrabFocus returned false");
+                }
+                Ok(())
+            });
+        });
+    }
+    section.append(&focus);
+
+    let kinds = gtk::DropDown::from_strings(
+        &SCROLL_TYPES
+            .iter()
+            .map(|(_, label)| *label)
+            .collect::<Vec<_>>(),
+    );
+    kinds.set_selected(SCROLL_TYPES.len() as u32 - 1);
+    let scroll_to = gtk::Button::with_label("Scroll to");
+    let hbox = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    hbox.append(&kinds);
+    hbox.append(&scroll_to);
+    section.row_widget("ScrollTo", &hbox);
+    {
+        let proxy = proxy.clone();
+        scroll_to.connect_clicked(move |button| {
+            let (kind, _) = SCROLL_TYPES[kinds.selected() as usize];
+            let proxy = proxy.clone();
+            let b = button.clone();
+            crate::spawn_fut(button, async move {
+                if !proxy.scroll_to(kind).await? {
+                    crate::toast(&b, "ScrollTo returned false");
+                }
+                Ok(())
+            });
+        });
+    }
+
+    let x = gtk::SpinButton::with_range(i32::MIN as f64, i32::MAX as f64, 1.);
+    let y = gtk::SpinButton::with_range(i32::MIN as f64, i32::MAX as f64, 1.);
+    let scroll_to_point = gtk::Button::with_label("Scroll");
+    let hbox = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    hbox.append(&x);
+    hbox.append(&y);
+    hbox.append(&scroll_to_point);
+    section.row_widget("ScrollToPoint", &hbox);
+    scroll_to_point.connect_clicked(move |button| {
+        let (x, y) = (x.value_as_int(), y.value_as_int());
+        let proxy = proxy.clone();
+        let b = button.clone();
+        crate::spawn_fut(button, async move {
+            if !proxy.scroll_to_point(CoordType::Window, x, y).await? {
+                crate::toast(&b, "ScrollToPoint returned false");
+            }
+            Ok(())
+        });
+    });
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use super::{Inspector, Section};
+use crate::node::{Node, ObjectKey};
+use atspi::{document::DocumentProxy, zbus};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Start object and offset, end object and offset, and whether the start is the active end.
+type TextSelection = (ObjectKey, i32, ObjectKey, i32, bool);
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Document");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<DocumentProxy<'static>>();
+    let proxy = proxy.await?;
+    section.result("Locale", proxy.get_locale().await);
+    section.result("Current page", proxy.current_page_number().await);
+    section.result("Page count", proxy.page_count().await);
+    match proxy.get_attributes().await {
+        Ok(map) => {
+            let mut map = map.into_iter().collect::<Vec<_>>();
+            map.sort();
+            for (key, value) in map {
+                section.row(&key, &value);
+            }
+        }
+        Err(err) => {
+            section.result::<String, _>("Attributes", Err(err));
+        }
+    }
+    // `DocumentProxy` in atspi 0.15 has no `GetTextSelections`
+    let selections = proxy
+        .inner()
+        .call::<_, Vec<TextSelection>>("GetTextSelections", &())
+        .await;
+    match selections {
+        Ok(selections) if selections.is_empty() => {
+            section.row("Text selections", "None");
+        }
+        Ok(selections) => {
+            for (index, (start, start_offset, end, end_offset, start_active)) in
+                selections.into_iter().enumerate()
+            {
+                let vbox = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .build();
+                let (start_label, end_label) = if start_active {
+                    ("from (active)", "to")
+                } else {
+                    ("from", "to (active)")
+                };
+                vbox.append(&super::value_label(&format!(
+                    "{start_label} offset {start_offset} in"
+                )));
+                vbox.append(&inspector.target_button(&start.0, &start.1));
+                vbox.append(&super::value_label(&format!(
+                    "{end_label} offset {end_offset} in"
+                )));
+                vbox.append(&inspector.target_button(&end.0, &end.1));
+                section.row_widget(&format!("Selection {index}"), &vbox);
+            }
+        }
+        Err(err) => {
+            section.result::<String, _>("Text selections", Err(err));
+        }
+    }
+    Ok(())
+}
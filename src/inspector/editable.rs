@@ -0,0 +1,115 @@
+use super::{Inspector, Section};
+use crate::{events::Subscription, node::Node};
+use atspi::{editable_text::EditableTextProxy, zbus};
+use futures_util::StreamExt;
+use gtk::prelude::*;
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// Events kept in the log under the editor.
+const LOG_LEN: usize = 20;
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Editable text");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<EditableTextProxy<'static>>();
+    let proxy = proxy.await?;
+    let text = gtk::Entry::builder()
+        .placeholder_text("Text")
+        .hexpand(true)
+        .build();
+    section.row_widget("Text", &text);
+    let start = gtk::SpinButton::with_range(-1., i32::MAX as f64, 1.);
+    let end = gtk::SpinButton::with_range(-1., i32::MAX as f64, 1.);
+    let range = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    range.append(&start);
+    range.append(&gtk::Label::new(Some("–")));
+    range.append(&end);
+    section.row_widget("Offsets", &range);
+    let buttons = gtk::FlowBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .column_spacing(6)
+        .row_spacing(6)
+        .build();
+    section.append(&buttons);
+
+    let calls: [(&str, &str); 5] = [
+        ("SetTextContents", "Set contents"),
+        ("InsertText", "Insert at start"),
+        ("DeleteText", "Delete range"),
+        ("CutText", "Cut range"),
+        ("PasteText", "Paste at start"),
+    ];
+    for (call, label) in calls {
+        let button = gtk::Button::builder()
+            .label(label)
+            .tooltip_text(call)
+            .build();
+        let proxy = proxy.clone();
+        let (text, start, end) = (text.clone(), start.clone(), end.clone());
+        button.connect_clicked(move |button| {
+            let proxy = proxy.clone();
+            let text = text.text().to_string();
+            let (start, end) = (start.value_as_int(), end.value_as_int());
+            let b = button.clone();
+            crate::spawn_fut(button, async move {
+                let ok = match call {
+                    "SetTextContents" => proxy.set_text_contents(&text).await?,
+                    "InsertText" => {
+                        let length = text.chars().count() as i32;
+                        proxy.insert_text(start, &text, length).await?
+                    }
+                    "DeleteText" => proxy.delete_text(start, end).await?,
+                    "CutText" => proxy.cut_text(start, end).await?,
+                    _ => proxy.paste_text(start).await?,
+                };
+                if !ok {
+                    crate::toast(&b, &format!("{call} returned false"));
+                }
+                Ok(())
+            });
+        });
+        buttons.insert(&button, -1);
+    }
+
+    let log = super::value_label("No text-changed events yet");
+    log.add_css_class("monospace");
+    log.add_css_class("caption");
+    section.row_widget("Events", &log);
+    let conn = node.borrow().conn.clone();
+    let (dest, path) = {
+        let node = node.borrow();
+        (node.dest.clone(), node.path.clone())
+    };
+    inspector.spawn_task(async move {
+        let _sub = Subscription::new(&conn, &["object:text-changed"]).await?;
+        let mut events = crate::events::stream(&conn);
+        let mut lines = VecDeque::new();
+        while let Some(event) = events.next().await {
+            if event.member != "TextChanged" || event.sender != dest || event.path != path {
+                continue;
+            }
+            let text = String::try_from(event.any_data.clone()).unwrap_or_default();
+            lines.push_front(format!(
+                "{} at {}, length {}: {text:?}",
+                event.kind, event.detail1, event.detail2
+            ));
+            lines.truncate(LOG_LEN);
+            log.set_label(&lines.make_contiguous().join("\n"));
+        }
+        Ok(())
+    });
+    Ok(())
+}
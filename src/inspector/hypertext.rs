@@ -0,0 +1,121 @@
+use super::{Inspector, Section};
+use crate::{node::Node, overview::Mark};
+use atspi::{
+    action::ActionProxy,
+    hyperlink::HyperlinkProxy,
+    hypertext::HypertextProxy,
+    text::TextProxy,
+    zbus::{self, zvariant::OwnedObjectPath},
+    CoordType,
+};
+use gtk::{gdk, prelude::*};
+use std::{cell::RefCell, rc::Rc};
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Links");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<HypertextProxy<'static>>();
+    let proxy = proxy.await?;
+    let text = node.borrow().proxy::<TextProxy<'static>>();
+    let text = text.await?;
+    let conn = node.borrow().conn.clone();
+    let count = proxy.get_n_links().await?;
+    if count <= 0 {
+        section.empty();
+    }
+    let mut marks = Vec::new();
+    for index in 0..count {
+        let (dest, path) = proxy.get_link(index).await?;
+        let link = HyperlinkProxy::builder(&conn)
+            .destination(dest.clone())?
+            .path(path.clone())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let start = link.start_index().await?;
+        let end = link.end_index().await?;
+        let grid = gtk::Grid::builder()
+            .row_spacing(2)
+            .column_spacing(8)
+            .margin_bottom(6)
+            .build();
+        let mut row = 0;
+        let mut attach = |label: &str, value: &gtk::Widget| {
+            grid.attach(&super::key_label(label), 0, row, 1, 1);
+            grid.attach(value, 1, row, 1, 1);
+            row += 1;
+        };
+        let offsets = super::value_label(&format!("{start}–{end}"));
+        attach("Offsets", offsets.upcast_ref());
+        for anchor in 0..link.n_anchors().await? {
+            let uri = match link.get_uri(anchor).await {
+                Ok(uri) => super::value_label(&uri),
+                Err(err) => {
+                    let label = super::value_label(&err.to_string());
+                    label.add_css_class("warning");
+                    label
+                }
+            };
+            attach(&format!("URI {anchor}"), uri.upcast_ref());
+            let (dest, path) = link.get_object(anchor).await?;
+            let target = inspector.target_button(&dest, &path);
+            attach(&format!("Anchor {anchor}"), target.upcast_ref());
+        }
+        let activate = gtk::Button::builder()
+            .label("Activate")
+            .halign(gtk::Align::Start)
+            .build();
+        {
+            let conn = conn.clone();
+            let (dest, path) = (dest.clone(), path.clone());
+            activate.connect_clicked(move |button| {
+                crate::spawn_fut(
+                    button,
+                    activate_link(conn.clone(), dest.clone(), path.clone()),
+                );
+            });
+        }
+        attach("", activate.upcast_ref());
+        section.append(&grid);
+        match text.get_range_extents(start, end, CoordType::Window).await {
+            Ok((x, y, w, h)) => marks.push(Mark::new(
+                gdk::Rectangle::new(x, y, w, h),
+                gdk::RGBA::new(0.1, 0.5, 0.9, 1.),
+            )),
+            Err(err) => log::debug!("GetRangeExtents({start}, {end}): {err}"),
+        }
+    }
+    inspector.overview.set_marks("inspector:links", marks);
+    Ok(())
+}
+
+/// Runs the first action of the link object, which toolkits expose as "activate" or "jump".
+async fn activate_link(
+    conn: zbus::Connection,
+    dest: String,
+    path: OwnedObjectPath,
+) -> anyhow::Result<()> {
+    let action = ActionProxy::builder(&conn)
+        .destination(dest)?
+        .path(path)?
+        .cache_properties(zbus::CacheProperties::No)
+        .build()
+        .await?;
+    if action.n_actions().await? <= 0 {
+        anyhow::bail!("The link has no actions");
+    }
+    if !action.do_action(0).await? {
+        anyhow::bail!("The application refused to activate the link");
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use super::Section;
+use crate::node::Node;
+use atspi::{image::ImageProxy, zbus, CoordType};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+pub async fn section(node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Image");
+    if let Err(err) = fill(&section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(section: &Section, node: &Rc<RefCell<Node>>) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<ImageProxy<'static>>();
+    let proxy = proxy.await?;
+    if node.borrow().is_unlabeled_image() {
+        let warning = super::value_label("Neither a name nor an image description");
+        warning.add_css_class("warning");
+        section.append(&warning);
+    }
+    section.result("Description", proxy.image_description().await);
+    section.result("Locale", proxy.image_locale().await);
+    let rect = |(x, y, w, h): (i32, i32, i32, i32)| format!("{w}×{h} at {x}, {y}");
+    section.result(
+        "Extents",
+        proxy.get_image_extents(CoordType::Window).await.map(rect),
+    );
+    section.result(
+        "Position",
+        proxy
+            .get_image_position(CoordType::Window)
+            .await
+            .map(|(x, y)| format!("{x}, {y}")),
+    );
+    section.result(
+        "Size",
+        proxy
+            .get_image_size()
+            .await
+            .map(|(w, h)| format!("{w}×{h}")),
+    );
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use super::{Inspector, Section};
+use crate::{events::Subscription, node::Node};
+use atspi::{selection::SelectionProxy, zbus};
+use futures_util::StreamExt;
+use gtk::{gdk, prelude::*};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// Events kept in the log under the controls.
+const LOG_LEN: usize = 20;
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Selection");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<SelectionProxy<'static>>();
+    let proxy = proxy.await?;
+    let targets = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .build();
+    section.row_widget("Selected", &targets);
+    show_selected(inspector, &proxy, &targets).await?;
+
+    // the loaded children can be truncated, so ask for the real count
+    let accessible = node.borrow().accessible();
+    let children = match accessible.await?.child_count().await {
+        Ok(count) => count.max(1),
+        Err(err) => {
+            log::debug!("ChildCount: {err}");
+            node.borrow().children.len().max(1) as i32
+        }
+    };
+    let index = gtk::SpinButton::with_range(0., (children - 1) as f64, 1.);
+    index.set_tooltip_text(Some("Child index"));
+    let select = gtk::Button::with_label("Select");
+    let deselect = gtk::Button::with_label("Deselect");
+    let hbox = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    hbox.append(&index);
+    hbox.append(&select);
+    hbox.append(&deselect);
+    section.row_widget("Child", &hbox);
+    let select_all = gtk::Button::with_label("Select all");
+    let clear = gtk::Button::with_label("Clear selection");
+    let hbox = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    hbox.append(&select_all);
+    hbox.append(&clear);
+    section.append(&hbox);
+
+    let log = super::value_label("No selection-changed events yet");
+    log.add_css_class("monospace");
+    log.add_css_class("caption");
+    section.row_widget("Events", &log);
+    let conn = node.borrow().conn.clone();
+    let (dest, path) = {
+        let node = node.borrow();
+        (node.dest.clone(), node.path.clone())
+    };
+    {
+        let inspector = inspector.clone();
+        let proxy = proxy.clone();
+        let targets = targets.clone();
+        inspector.clone().spawn_task(async move {
+            let _sub = Subscription::new(&conn, &["object:selection-changed"]).await?;
+            let mut events = crate::events::queued(&conn, move |event| {
+                event.member == "SelectionChanged" && event.sender == dest && event.path == path
+            });
+            let mut lines = VecDeque::new();
+            while let Some(event) = events.next().await {
+                // a burst of changes only needs the selection read once
+                let event = crate::events::latest(&mut events, event);
+                let time = crate::monitor::format_time(event.time);
+                lines.push_front(match show_selected(&inspector, &proxy, &targets).await {
+                    Ok(count) => format!("{time} {count} selected"),
+                    Err(err) => format!("{time} {err}"),
+                });
+                lines.truncate(LOG_LEN);
+                log.set_label(&lines.make_contiguous().join("\n"));
+            }
+            Ok(())
+        });
+    }
+
+    let inspector = inspector.clone();
+    let run = move |button: &gtk::Button, call: &'static str| {
+        let proxy = proxy.clone();
+        let inspector = inspector.clone();
+        let targets = targets.clone();
+        let child = index.value_as_int();
+        let b = button.clone();
+        crate::spawn_fut(button, async move {
+            let ok = match call {
+                "SelectChild" => proxy.select_child(child).await?,
+                "DeselectChild" => proxy.deselect_child(child).await?,
+                "SelectAll" => proxy.select_all().await?,
+                _ => proxy.clear_selection().await?,
+            };
+            if !ok {
+                crate::toast(&b, &format!("{call} returned false"));
+            }
+            // not every toolkit reports its own changes
+            show_selected(&inspector, &proxy, &targets).await?;
+            Ok(())
+        });
+    };
+    let run = Rc::new(run);
+    for (button, call) in [
+        (select, "SelectChild"),
+        (deselect, "DeselectChild"),
+        (select_all, "SelectAll"),
+        (clear, "ClearSelection"),
+    ] {
+        let run = run.clone();
+        button.connect_clicked(move |button| run(button, call));
+    }
+    Ok(())
+}
+
+/// Lists the selected children in `targets` and outlines them. Returns how many there are.
+async fn show_selected(
+    inspector: &Inspector,
+    proxy: &SelectionProxy<'static>,
+    targets: &gtk::Box,
+) -> zbus::Result<usize> {
+    let count = proxy.n_selected_children().await?;
+    let mut selected = Vec::new();
+    for index in 0..count {
+        selected.push(proxy.get_selected_child(index).await?);
+    }
+    while let Some(child) = targets.first_child() {
+        targets.remove(&child);
+    }
+    if selected.is_empty() {
+        let label = super::value_label("None");
+        label.add_css_class("dim-label");
+        targets.append(&label);
+    }
+    for (dest, path) in &selected {
+        targets.append(&inspector.target_button(dest, path));
+    }
+    let count = selected.len();
+    inspector.mark_objects(
+        "inspector:selection",
+        selected,
+        gdk::RGBA::new(0.2, 0.6, 1., 1.),
+    );
+    Ok(count)
+}
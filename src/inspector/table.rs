@@ -0,0 +1,248 @@
+use super::{Inspector, Section};
+use crate::node::{Node, ObjectKey};
+use atspi::{
+    accessible::AccessibleProxy,
+    table::TableProxy,
+    table_cell::TableCellProxy,
+    zbus::{self, zvariant::OwnedObjectPath},
+};
+use gtk::{gdk, prelude::*};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+/// Upper bound on `GetAccessibleAt` calls for one grid.
+const MAX_CELLS: i32 = 1000;
+
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Table");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+pub async fn cell_section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Table cell");
+    if let Err(err) = fill_cell(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<TableProxy<'static>>();
+    let proxy = proxy.await?;
+    let conn = node.borrow().conn.clone();
+    let rows = proxy.n_rows().await?;
+    let columns = proxy.n_columns().await?;
+    section.row("Size", &format!("{rows} rows × {columns} columns"));
+    object_row(inspector, section, "Caption", proxy.caption().await);
+    object_row(inspector, section, "Summary", proxy.summary().await);
+    let selected_rows = proxy.get_selected_rows().await?;
+    let selected_columns = proxy.get_selected_columns().await?;
+    section.row("Selected rows", &join(&selected_rows));
+    section.row("Selected columns", &join(&selected_columns));
+
+    let shown_columns = columns.clamp(0, MAX_CELLS);
+    let shown = rows.min(MAX_CELLS / shown_columns.max(1)).max(0);
+    let grid = gtk::Grid::builder()
+        .row_spacing(2)
+        .column_spacing(2)
+        .build();
+    for column in 0..shown_columns {
+        let header = proxy.get_column_header(column).await?;
+        let label = match object_label(inspector, &conn, &header).await {
+            Some(label) => label,
+            None => proxy.get_column_description(column).await?,
+        };
+        grid.attach(&header_label(&label), column + 1, 0, 1, 1);
+    }
+    let mut covered = HashSet::new();
+    for row in 0..shown {
+        let header = proxy.get_row_header(row).await?;
+        let label = match object_label(inspector, &conn, &header).await {
+            Some(label) => label,
+            None => proxy.get_row_description(row).await?,
+        };
+        grid.attach(&header_label(&label), 0, row + 1, 1, 1);
+        for column in 0..shown_columns {
+            if covered.contains(&(row, column)) {
+                continue;
+            }
+            let cell = proxy.get_accessible_at(row, column).await?;
+            // spans past the shown cells are cut off, whatever the toolkit reports
+            let row_span = proxy
+                .get_row_extent_at(row, column)
+                .await?
+                .clamp(1, shown - row);
+            let column_span = proxy
+                .get_column_extent_at(row, column)
+                .await?
+                .clamp(1, shown_columns - column);
+            for r in row..row + row_span {
+                for c in column..column + column_span {
+                    covered.insert((r, c));
+                }
+            }
+            let label = object_label(inspector, &conn, &cell)
+                .await
+                .unwrap_or_else(|| "—".to_owned());
+            let button = gtk::Button::builder()
+                .child(
+                    &gtk::Label::builder()
+                        .label(label)
+                        .ellipsize(gtk::pango::EllipsizeMode::End)
+                        .max_width_chars(16)
+                        .build(),
+                )
+                .tooltip_text(format!(
+                    "({row}, {column}), spans {row_span} × {column_span}\n{}",
+                    cell.1.as_str()
+                ))
+                .build();
+            if selected_rows.contains(&row) || selected_columns.contains(&column) {
+                button.add_css_class("suggested-action");
+            }
+            let inspector = inspector.clone();
+            button.connect_clicked(move |_| {
+                inspector.mark_objects(
+                    "inspector:cell",
+                    vec![cell.clone()],
+                    gdk::RGBA::new(0.9, 0.5, 0., 1.),
+                );
+            });
+            grid.attach(&button, column + 1, row + 1, column_span, row_span);
+        }
+    }
+    if shown < rows {
+        section.row("Shown", &format!("{shown} of {rows} rows"));
+    }
+    if shown_columns < columns {
+        section.row("Shown", &format!("{shown_columns} of {columns} columns"));
+    }
+    section.append(
+        &gtk::ScrolledWindow::builder()
+            .child(&grid)
+            .max_content_height(400)
+            .propagate_natural_height(true)
+            .build(),
+    );
+    Ok(())
+}
+
+async fn fill_cell(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<TableCellProxy<'static>>();
+    let proxy = proxy.await?;
+    section.result(
+        "Position",
+        proxy
+            .position()
+            .await
+            .map(|(row, column)| format!("row {row}, column {column}")),
+    );
+    section.result("Row span", proxy.row_span().await);
+    section.result("Column span", proxy.column_span().await);
+    object_row(inspector, section, "Table", proxy.table().await);
+    for (label, headers) in [
+        ("Row headers", proxy.get_row_header_cells().await),
+        ("Column headers", proxy.get_column_header_cells().await),
+    ] {
+        match headers {
+            Ok(headers) if headers.is_empty() => {
+                section.row(label, "None");
+            }
+            Ok(headers) => {
+                let targets = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .build();
+                for (dest, path) in &headers {
+                    targets.append(&inspector.target_button(dest, path));
+                }
+                section.row_widget(label, &targets);
+            }
+            Err(err) => {
+                section.result::<String, _>(label, Err(err));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn object_row(
+    inspector: &Inspector,
+    section: &Section,
+    label: &str,
+    res: zbus::Result<(String, OwnedObjectPath)>,
+) {
+    match res {
+        Ok((_, path)) if path.as_str() == NULL_PATH => {
+            section.row(label, "None");
+        }
+        Ok((dest, path)) => section.row_widget(label, &inspector.target_button(&dest, &path)),
+        Err(err) => {
+            section.result::<String, _>(label, Err(err));
+        }
+    }
+}
+
+/// Name or role of an object, from the loaded tree when possible.
+async fn object_label(
+    inspector: &Inspector,
+    conn: &zbus::Connection,
+    (dest, path): &ObjectKey,
+) -> Option<String> {
+    if path.as_str() == NULL_PATH {
+        return None;
+    }
+    if let Some(node) = inspector.overview.find(dest, path) {
+        let node = node.borrow();
+        return Some(if node.name.is_empty() {
+            node.role.name().to_owned()
+        } else {
+            node.name.clone()
+        });
+    }
+    let res: zbus::Result<String> = async {
+        AccessibleProxy::builder(conn)
+            .destination(dest.clone())?
+            .path(path.clone())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?
+            .name()
+            .await
+    }
+    .await;
+    res.map_err(|err| log::debug!("Name of {}: {err}", path.as_str()))
+        .ok()
+}
+
+fn header_label(label: &str) -> gtk::Label {
+    gtk::Label::builder()
+        .label(label)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .max_width_chars(16)
+        .css_classes(["heading"])
+        .build()
+}
+
+fn join(items: &[i32]) -> String {
+    if items.is_empty() {
+        "None".to_owned()
+    } else {
+        items
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Inspector, Section};
+use crate::{node::Node, overview::Mark};
+use atspi::{text::TextProxy, zbus, CoordType};
+use gtk::{gdk, prelude::*};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// Guards against toolkits that never advance the run end.
+const MAX_RUNS: usize = 10_000;
+
+const RUN_COLORS: &[&str] = &[
+    "rgba(53,132,228,0.25)",
+    "rgba(46,194,126,0.25)",
+    "rgba(246,211,45,0.25)",
+    "rgba(255,120,0,0.25)",
+    "rgba(145,65,172,0.25)",
+    "rgba(224,27,36,0.25)",
+];
+
+pub async fn section(inspector: &Inspector, node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Text");
+    if let Err(err) = fill(inspector, &section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(
+    inspector: &Inspector,
+    section: &Section,
+    node: &Rc<RefCell<Node>>,
+) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<TextProxy<'static>>();
+    let proxy = proxy.await?;
+    let count = proxy.character_count().await?;
+    section.row("Characters", &count.to_string());
+    section.result("Caret offset", proxy.caret_offset().await);
+    let selections = proxy.get_n_selections().await?;
+    if selections == 0 {
+        section.row("Selections", "None");
+    }
+    for index in 0..selections {
+        section.result(
+            &format!("Selection {index}"),
+            proxy
+                .get_selection(index)
+                .await
+                .map(|(start, end)| format!("{start}–{end}")),
+        );
+    }
+
+    let text = proxy.get_text(0, count).await?;
+    let buffer = gtk::TextBuffer::new(None);
+    buffer.set_text(&text);
+    let view = gtk::TextView::builder()
+        .buffer(&buffer)
+        .editable(false)
+        .cursor_visible(false)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .css_classes(["monospace"])
+        .build();
+    section.append(&gtk::Frame::builder().child(&view).build());
+
+    let runs = attribute_runs(&proxy, count).await?;
+    let runs_label = super::value_label("");
+    let mut lines = Vec::new();
+    for (index, (start, end, attrs)) in runs.iter().enumerate() {
+        if attrs.is_empty() {
+            continue;
+        }
+        let color = RUN_COLORS[index % RUN_COLORS.len()];
+        let tag = buffer.create_tag(None, &[("background", &color)]).unwrap();
+        buffer.apply_tag(
+            &tag,
+            &buffer.iter_at_offset(*start),
+            &buffer.iter_at_offset(*end),
+        );
+        let mut attrs = attrs
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>();
+        attrs.sort();
+        lines.push(format!("{start}–{end}: {}", attrs.join(", ")));
+    }
+    if lines.is_empty() {
+        runs_label.set_label("No attributes");
+        runs_label.add_css_class("dim-label");
+    } else {
+        runs_label.set_label(&lines.join("\n"));
+    }
+    section.append(&runs_label);
+
+    let overview = inspector.overview.clone();
+    let hovered = Rc::new(Cell::new(-1));
+    let motion = gtk::EventControllerMotion::new();
+    {
+        let overview = overview.clone();
+        let hovered = hovered.clone();
+        motion.connect_motion(move |ctrl, x, y| {
+            let view = ctrl.widget().downcast::<gtk::TextView>().unwrap();
+            let (bx, by) =
+                view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+            let offset = view
+                .iter_at_location(bx, by)
+                .map_or(-1, |iter| iter.offset());
+            if hovered.replace(offset) == offset {
+                return;
+            }
+            if offset < 0 {
+                overview.set_marks("inspector:char", Vec::new());
+                return;
+            }
+            let proxy = proxy.clone();
+            let overview = overview.clone();
+            let hovered = hovered.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let extents = proxy.get_character_extents(offset, CoordType::Window).await;
+                if hovered.get() != offset {
+                    return;
+                }
+                match extents {
+                    Ok((x, y, w, h)) => overview.set_marks(
+                        "inspector:char",
+                        vec![Mark::new(
+                            gdk::Rectangle::new(x, y, w, h),
+                            gdk::RGBA::new(0.2, 0.8, 0.2, 1.),
+                        )],
+                    ),
+                    Err(err) => log::debug!("GetCharacterExtents({offset}): {err}"),
+                }
+            });
+        });
+    }
+    motion.connect_leave(move |_| {
+        hovered.set(-1);
+        overview.set_marks("inspector:char", Vec::new());
+    });
+    view.add_controller(motion);
+    Ok(())
+}
+
+/// Walks `GetAttributeRun` across the whole text.
+async fn attribute_runs(
+    proxy: &TextProxy<'static>,
+    count: i32,
+) -> zbus::Result<Vec<(i32, i32, HashMap<String, String>)>> {
+    let mut runs = Vec::new();
+    let mut offset = 0;
+    while offset < count && runs.len() < MAX_RUNS {
+        let (attrs, start, end) = proxy.get_attribute_run(offset, false).await?;
+        if end <= offset {
+            break;
+        }
+        runs.push((start.max(0), end.min(count), attrs));
+        offset = end;
+    }
+    Ok(runs)
+}
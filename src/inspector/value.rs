@@ -0,0 +1,64 @@
+use super::Section;
+use crate::node::Node;
+use atspi::{value::ValueProxy, zbus};
+use gtk::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+pub async fn section(node: &Rc<RefCell<Node>>) -> Section {
+    let section = Section::new("Value");
+    if let Err(err) = fill(&section, node).await {
+        section.error(&err);
+    }
+    section
+}
+
+async fn fill(section: &Section, node: &Rc<RefCell<Node>>) -> zbus::Result<()> {
+    let proxy = node.borrow().proxy::<ValueProxy<'static>>();
+    let proxy = proxy.await?;
+    section.result("Minimum", proxy.minimum_value().await);
+    section.result("Maximum", proxy.maximum_value().await);
+    section.result("Minimum increment", proxy.minimum_increment().await);
+    let current = section.result("Current", proxy.current_value().await);
+    // the textual value is missing from atspi 0.15's `ValueProxy`
+    section.result("Text", proxy.inner().get_property::<String>("Text").await);
+
+    // deliberately not clamped to the reported bounds, which are often what is being debugged
+    let entry = gtk::Entry::builder()
+        .input_purpose(gtk::InputPurpose::Number)
+        .placeholder_text("New value")
+        .hexpand(true)
+        .build();
+    let button = gtk::Button::with_label("Set");
+    let hbox = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    hbox.append(&entry);
+    hbox.append(&button);
+    section.append(&hbox);
+    let input = entry.clone();
+    let set = move |widget: &gtk::Widget| {
+        let value = match input.text().trim().parse::<f64>() {
+            Ok(value) => value,
+            Err(err) => {
+                crate::toast(widget, &format!("Not a number: {err}"));
+                return;
+            }
+        };
+        let proxy = proxy.clone();
+        let current = current.clone();
+        crate::spawn_fut(widget, async move {
+            proxy.set_current_value(value).await?;
+            current.set_label(&proxy.current_value().await?.to_string());
+            current.remove_css_class("warning");
+            Ok(())
+        });
+    };
+    let set = Rc::new(set);
+    {
+        let set = set.clone();
+        button.connect_clicked(move |button| set(button.upcast_ref()));
+    }
+    entry.connect_activate(move |entry| set(entry.upcast_ref()));
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use crate::node::{Cache, Node, ObjectKey, Truncation};
+use atspi::{accessible::AccessibleProxy, zbus};
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Nodes fetched concurrently.
+    pub max_in_flight: usize,
+    /// Levels below the starting node.
+    pub max_depth: usize,
+    /// Nodes in a single walk.
+    pub max_nodes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 16,
+            max_depth: 64,
+            max_nodes: 50_000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Progress {
+    pub discovered: usize,
+    pub loaded: usize,
+    pub errors: usize,
+    pub elapsed: Duration,
+    pub finished: bool,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f64 {
+        if self.discovered == 0 {
+            0.
+        } else {
+            (self.loaded + self.errors) as f64 / self.discovered as f64
+        }
+    }
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} nodes", self.loaded, self.discovered)?;
+        if self.errors > 0 {
+            write!(f, ", {} errors", self.errors)?;
+        }
+        write!(f, ", {:.1}s", self.elapsed.as_secs_f64())
+    }
+}
+
+/// Objects reached by the walks into one tree, shared by the loaders that extend it.
+pub type Visited = Rc<RefCell<HashSet<ObjectKey>>>;
+
+struct Job {
+    parent: Rc<RefCell<Node>>,
+    index: usize,
+    depth: usize,
+    key: ObjectKey,
+}
+
+/// Walks a subtree breadth-first, fetching up to `max_in_flight` nodes at once. Objects seen
+/// earlier in the walk and anything past the limits are not fetched, and the node where the walk
+/// stopped is marked instead. Dropping the future returned by `fill` cancels the walk.
+pub struct Loader {
+    conn: zbus::Connection,
+    cache: Option<Rc<Cache>>,
+    limits: Limits,
+    started: Instant,
+    visited: Visited,
+    /// Indices of the children attached so far, per parent, to keep siblings in order.
+    slots: RefCell<HashMap<*const RefCell<Node>, Vec<usize>>>,
+    discovered: Cell<usize>,
+    loaded: Cell<usize>,
+    errors: Cell<usize>,
+    on_progress: Option<Box<dyn Fn(Progress)>>,
+}
+
+impl Loader {
+    pub fn new(conn: &zbus::Connection, cache: Option<Rc<Cache>>, limits: Limits) -> Self {
+        Self {
+            conn: conn.clone(),
+            cache,
+            limits,
+            started: Instant::now(),
+            visited: Default::default(),
+            slots: Default::default(),
+            discovered: Cell::new(0),
+            loaded: Cell::new(0),
+            errors: Cell::new(0),
+            on_progress: None,
+        }
+    }
+    /// Shares the objects already in the tree, so a walk that extends it neither loads them
+    /// again nor counts from zero towards `max_nodes`.
+    pub fn with_visited(mut self, visited: Visited) -> Self {
+        self.visited = visited;
+        self
+    }
+    pub fn with_progress(mut self, f: impl Fn(Progress) + 'static) -> Self {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
+    pub fn progress(&self) -> Progress {
+        Progress {
+            discovered: self.discovered.get(),
+            loaded: self.loaded.get(),
+            errors: self.errors.get(),
+            elapsed: self.started.elapsed(),
+            finished: false,
+        }
+    }
+    /// Loads the node a walk starts from.
+    pub async fn root(&self, key: &ObjectKey) -> (Rc<RefCell<Node>>, Vec<ObjectKey>) {
+        self.discovered.set(self.discovered.get() + 1);
+        self.visited.borrow_mut().insert(key.clone());
+        let (node, children) = self.fetch(key).await;
+        self.loaded(&node);
+        self.report();
+        (Rc::new(RefCell::new(node)), children)
+    }
+    /// Loads a single node along with the list of its children. Failures are recorded on the node.
+    pub async fn fetch(&self, key: &ObjectKey) -> (Node, Vec<ObjectKey>) {
+        let proxy: zbus::Result<AccessibleProxy<'static>> = async {
+            AccessibleProxy::builder(&self.conn)
+                .destination(key.0.clone())?
+                .path(key.1.clone())?
+                .build()
+                .await
+        }
+        .await;
+        let proxy = match proxy {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                let mut node = Node::empty(&self.conn, key.0.clone(), key.1.clone());
+                node.record::<(), _>("Accessible", Err(err));
+                return (node, Vec::new());
+            }
+        };
+        let mut node = Node::with_cache(proxy.clone(), self.cache.as_deref()).await;
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.children(&node.dest, &node.path));
+        let children = match cached {
+            Some(children) => children,
+            None => {
+                let start = Instant::now();
+                let children = proxy.get_children().await;
+                if let Some(cache) = &self.cache {
+                    cache.record_round_trip(start.elapsed());
+                }
+                node.record("GetChildren", children).unwrap_or_default()
+            }
+        };
+        (node, children)
+    }
+    /// Loads the subtrees of `children` and attaches them to `node`, which is `depth` levels below
+    /// the root, in order.
+    pub async fn fill(&self, node: &Rc<RefCell<Node>>, depth: usize, children: Vec<ObjectKey>) {
+        let mut pending = VecDeque::new();
+        self.discover(node, depth, children, &mut pending);
+        let mut in_flight = FuturesUnordered::new();
+        loop {
+            while in_flight.len() < self.limits.max_in_flight.max(1) {
+                let Some(job) = pending.pop_front() else {
+                    break;
+                };
+                in_flight.push(async move {
+                    let (child, children) = self.fetch(&job.key).await;
+                    (job, child, children)
+                });
+            }
+            let Some((job, child, children)) = in_flight.next().await else {
+                break;
+            };
+            self.loaded(&child);
+            let child = Rc::new(RefCell::new(child));
+            self.attach(&job.parent, job.index, child.clone());
+            self.discover(&child, job.depth, children, &mut pending);
+            self.report();
+        }
+    }
+    /// Loads a child that appeared under `parent`, `depth` levels below the root, along with its
+    /// subtree. Returns `None` and marks `parent` when a limit stops it.
+    pub async fn child(
+        &self,
+        parent: &Rc<RefCell<Node>>,
+        depth: usize,
+        key: ObjectKey,
+    ) -> Option<Rc<RefCell<Node>>> {
+        if depth >= self.limits.max_depth {
+            parent.borrow_mut().truncated = Some(Truncation::Depth);
+            return None;
+        }
+        if self.visited.borrow().len() >= self.limits.max_nodes {
+            parent.borrow_mut().truncated = Some(Truncation::Count);
+            return None;
+        }
+        if !self.visited.borrow_mut().insert(key.clone()) {
+            let mut node = Node::empty(&self.conn, key.0, key.1);
+            node.truncated = Some(Truncation::Cycle);
+            return Some(Rc::new(RefCell::new(node)));
+        }
+        self.discovered.set(self.discovered.get() + 1);
+        let (child, children) = self.fetch(&key).await;
+        self.loaded(&child);
+        let child = Rc::new(RefCell::new(child));
+        self.fill(&child, depth + 1, children).await;
+        Some(child)
+    }
+    /// Drops a removed subtree from the visited objects so it can be loaded again.
+    pub fn forget(&self, node: &Rc<RefCell<Node>>) {
+        let node = node.borrow();
+        // the object itself is still shown where it was first reached
+        if node.truncated == Some(Truncation::Cycle) {
+            return;
+        }
+        self.visited
+            .borrow_mut()
+            .remove(&(node.dest.clone(), node.path.clone()));
+        for child in &node.children {
+            self.forget(child);
+        }
+    }
+    fn attach(&self, parent: &Rc<RefCell<Node>>, index: usize, child: Rc<RefCell<Node>>) {
+        let mut slots = self.slots.borrow_mut();
+        let slots = slots.entry(Rc::as_ptr(parent)).or_default();
+        let pos = slots.partition_point(|&i| i < index);
+        slots.insert(pos, index);
+        Node::insert_child(parent, pos, child);
+    }
+    fn discover(
+        &self,
+        parent: &Rc<RefCell<Node>>,
+        depth: usize,
+        children: Vec<ObjectKey>,
+        pending: &mut VecDeque<Job>,
+    ) {
+        if !children.is_empty() && depth >= self.limits.max_depth {
+            parent.borrow_mut().truncated = Some(Truncation::Depth);
+            return;
+        }
+        for (index, key) in children.into_iter().enumerate() {
+            if self.visited.borrow().len() >= self.limits.max_nodes {
+                parent.borrow_mut().truncated = Some(Truncation::Count);
+                return;
+            }
+            if !self.visited.borrow_mut().insert(key.clone()) {
+                log::debug!("{} was already reached in this walk", key.1.as_str());
+                let mut node = Node::empty(&self.conn, key.0, key.1);
+                node.truncated = Some(Truncation::Cycle);
+                self.attach(parent, index, Rc::new(RefCell::new(node)));
+                continue;
+            }
+            self.discovered.set(self.discovered.get() + 1);
+            pending.push_back(Job {
+                parent: parent.clone(),
+                index,
+                depth: depth + 1,
+                key,
+            });
+        }
+    }
+    fn loaded(&self, node: &Node) {
+        if let Some(error) = &node.error {
+            log::debug!("Failed to load {}: {error}", node.path.as_str());
+            self.errors.set(self.errors.get() + 1);
+        } else {
+            self.loaded.set(self.loaded.get() + 1);
+        }
+    }
+    fn report(&self) {
+        if let Some(f) = &self.on_progress {
+            f(self.progress());
+        }
+    }
+}
@@ -1,11 +1,25 @@
 use adw::prelude::*;
 use atspi::accessible::AccessibleProxy;
+use std::rc::Rc;
 
+mod app;
+mod caret;
+mod errors;
+mod events;
+mod focus;
+mod inspector;
+mod loader;
+mod monitor;
+mod node;
 mod overview;
+mod recording;
+mod stats;
+mod tree;
 
 struct Root {
     name: String,
     proxy: AccessibleProxy<'static>,
+    app: Rc<app::AppInfo>,
 }
 
 fn build_ui(app: &adw::Application) {
@@ -51,13 +65,27 @@ fn build_ui(app: &adw::Application) {
                 .downcast::<glib::BoxedAnyObject>()
                 .unwrap();
             let root = obj.borrow::<Root>();
-            item.set_child(Some(
+            let vbox = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .margin_top(2)
+                .margin_bottom(2)
+                .build();
+            vbox.append(
                 &gtk::Label::builder()
                     .label(&root.name)
                     .xalign(0.)
                     .ellipsize(gtk::pango::EllipsizeMode::Middle)
                     .build(),
-            ));
+            );
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(root.app.subtitle())
+                    .xalign(0.)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .css_classes(["caption", "dim-label"])
+                    .build(),
+            );
+            item.set_child(Some(&vbox));
             item.set_activatable(false);
         });
         let list = gtk::ListView::new(Some(select.clone()), Some(factory));
@@ -82,6 +110,11 @@ fn build_ui(app: &adw::Application) {
             });
         }
         header.pack_end(&reload_button);
+        let follow_button = gtk::ToggleButton::builder()
+            .icon_name("find-location-symbolic")
+            .tooltip_text("Follow focus")
+            .build();
+        header.pack_start(&follow_button);
         vbox.append(&header);
         vbox.append(&scroll);
         let scroll_page = leaflet.append(&vbox);
@@ -122,7 +155,169 @@ fn build_ui(app: &adw::Application) {
         let overview = overview::Overview::new();
         overview.set_hexpand(true);
         overview.set_vexpand(true);
-        vbox.append(&overview);
+
+        let progress = gtk::ProgressBar::builder()
+            .show_text(true)
+            .valign(gtk::Align::Center)
+            .visible(false)
+            .build();
+        header.pack_end(&progress);
+        overview.connect_load_progress(move |_, p| {
+            progress.set_visible(!p.finished);
+            progress.set_fraction(p.fraction());
+            progress.set_text(Some(&p.to_string()));
+        });
+        let settings = gtk::Grid::builder()
+            .row_spacing(6)
+            .column_spacing(8)
+            .build();
+        let limits = overview.limits();
+        let fields: [(&str, f64, f64, fn(&mut loader::Limits) -> &mut usize); 3] = [
+            (
+                "Parallel requests",
+                256.,
+                limits.max_in_flight as f64,
+                |l| &mut l.max_in_flight,
+            ),
+            ("Maximum depth", 1024., limits.max_depth as f64, |l| {
+                &mut l.max_depth
+            }),
+            ("Maximum nodes", 1_000_000., limits.max_nodes as f64, |l| {
+                &mut l.max_nodes
+            }),
+        ];
+        for (row, (label, max, value, field)) in fields.into_iter().enumerate() {
+            let spin = gtk::SpinButton::with_range(1., max, 1.);
+            spin.set_value(value);
+            let overview = overview.clone();
+            spin.connect_value_changed(move |spin| {
+                let mut limits = overview.limits();
+                *field(&mut limits) = spin.value_as_int() as usize;
+                overview.set_limits(limits);
+            });
+            settings.attach(
+                &gtk::Label::builder().label(label).xalign(0.).build(),
+                0,
+                row as i32,
+                1,
+                1,
+            );
+            settings.attach(&spin, 1, row as i32, 1, 1);
+        }
+        let settings_button = gtk::MenuButton::builder()
+            .icon_name("emblem-system-symbolic")
+            .popover(&gtk::Popover::builder().child(&settings).build())
+            .build();
+        header.pack_end(&settings_button);
+        let tree = tree::Tree::new(&overview.model());
+        let inspector = inspector::Inspector::new(&overview);
+        {
+            let overview = overview.clone();
+            let inspector = inspector.clone();
+            tree.connect_selected(move |node| {
+                overview.set_picked(node.clone());
+                if node.is_some() {
+                    inspector.set_node(node);
+                }
+            });
+        }
+        {
+            let tree = tree.clone();
+            let inspector = inspector.clone();
+            overview.connect_node_picked(move |_, node| {
+                tree.select(&node);
+                inspector.set_node(Some(node));
+            });
+        }
+        {
+            let tree = tree.clone();
+            let inspector = inspector.clone();
+            overview.connect_node_changed(move |_, node| {
+                tree.refresh(&node);
+                inspector.refresh(&node);
+            });
+        }
+        let errors = errors::ErrorList::new(&overview.errors());
+        {
+            let tree = tree.clone();
+            errors.connect_activated(move |node| tree.select(&node));
+        }
+        let app_page = app::AppPage::new();
+        let caret_page = caret::CaretPage::new(bus.connection(), &overview);
+        let sidebar = gtk::Stack::new();
+        sidebar.add_titled(&tree.scroll, Some("tree"), "Tree");
+        let errors_page = sidebar.add_titled(&errors.scroll, Some("errors"), "Errors");
+        sidebar.add_titled(&app_page.scroll, Some("app"), "Application");
+        sidebar.add_titled(&caret_page.widget, Some("caret"), "Caret");
+        overview
+            .errors()
+            .connect_items_changed(move |errors, _, _, _| {
+                errors_page.set_title(Some(&match errors.n_items() {
+                    0 => "Errors".to_owned(),
+                    n => format!("Errors ({n})"),
+                }));
+            });
+        let sidebar_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .build();
+        sidebar_box.append(
+            &gtk::StackSwitcher::builder()
+                .stack(&sidebar)
+                .halign(gtk::Align::Center)
+                .margin_top(4)
+                .margin_bottom(4)
+                .build(),
+        );
+        sidebar_box.append(&sidebar);
+        let paned = gtk::Paned::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .start_child(&sidebar_box)
+            .end_child(
+                &gtk::Paned::builder()
+                    .orientation(gtk::Orientation::Horizontal)
+                    .start_child(&overview)
+                    .end_child(&inspector.scroll)
+                    .shrink_end_child(false)
+                    .resize_end_child(false)
+                    .build(),
+            )
+            .shrink_start_child(false)
+            .resize_start_child(false)
+            .vexpand(true)
+            .build();
+        {
+            let conn = bus.connection().clone();
+            let select = select.clone();
+            let overview = overview.clone();
+            let handle = std::cell::RefCell::new(None::<glib::JoinHandle<()>>);
+            follow_button.connect_toggled(move |button| {
+                if let Some(handle) = handle.take() {
+                    handle.abort();
+                }
+                if button.is_active() {
+                    let fut = focus::follow(conn.clone(), select.clone(), overview.clone());
+                    handle.replace(Some(spawn_fut(button, fut)));
+                }
+            });
+        }
+        let monitor = monitor::Monitor::new(bus.connection(), &overview);
+        let pages = gtk::Stack::builder().vexpand(true).build();
+        pages.add_titled(&paned, Some("inspect"), "Inspect");
+        pages.add_titled(&monitor.widget, Some("events"), "Events");
+        let stats = stats::StatsPage::new(bus.connection(), &overview);
+        pages.add_titled(&stats.widget, Some("stats"), "Statistics");
+        header.pack_start(&gtk::StackSwitcher::builder().stack(&pages).build());
+        vbox.append(&pages);
+        vbox.append(&monitor.timeline);
+        {
+            // the marks of a replayed event are drawn on the overview
+            let pages = pages.clone();
+            monitor.timeline.connect_visible_notify(move |timeline| {
+                if timeline.is_visible() {
+                    pages.set_visible_child_name("inspect");
+                }
+            });
+        }
         let main_page = leaflet.append(&vbox);
         main_page.set_name(Some("overview"));
 
@@ -139,11 +334,16 @@ fn build_ui(app: &adw::Application) {
                 let root = obj.borrow::<Root>();
                 label.set_label(&root.name);
                 leaflet.set_visible_child_name("overview");
+                caret_page.reset();
                 overview.set_accessible(root.proxy.clone());
+                app_page.set_app(Some(root.app.clone()));
             } else {
                 label.set_label("SPInspector");
                 leaflet.set_visible_child_name("list");
                 overview.clear();
+                inspector.set_node(None);
+                app_page.set_app(None);
+                caret_page.reset();
             }
         });
 
@@ -167,6 +367,8 @@ async fn reload(bus: atspi::zbus::Connection, model: gio::ListStore) -> anyhow::
             .build()
             .await?;
         let app_name = app.name().await?;
+        let info =
+            Rc::new(app::AppInfo::load(&bus, app.destination().as_str(), app_name.clone()).await);
         for (dest, path) in app.get_children().await? {
             let proxy = atspi::accessible::AccessibleProxy::builder(&bus)
                 .destination(dest)?
@@ -179,7 +381,11 @@ async fn reload(bus: atspi::zbus::Connection, model: gio::ListStore) -> anyhow::
             } else {
                 name
             };
-            model.append(&glib::BoxedAnyObject::new(Root { name, proxy }));
+            model.append(&glib::BoxedAnyObject::new(Root {
+                name,
+                proxy,
+                app: info.clone(),
+            }));
         }
     }
     Ok(())
@@ -203,6 +409,13 @@ fn spawn_fut(
     })
 }
 
+fn toast(widget: &impl glib::IsA<gtk::Widget>, title: &str) {
+    if let Some(overlay) = widget.ancestor(adw::ToastOverlay::static_type()) {
+        let overlay = overlay.downcast::<adw::ToastOverlay>().unwrap();
+        overlay.add_toast(adw::Toast::new(title));
+    }
+}
+
 fn main() -> glib::ExitCode {
     static LOGGER: glib::GlibLogger = glib::GlibLogger::new(
         glib::GlibLoggerFormat::Plain,
@@ -0,0 +1,642 @@
+use crate::{
+    events::{Event, Subscription},
+    node::ObjectKey,
+    overview::{Mark, Overview},
+    tree::row_label,
+};
+use atspi::{accessible::AccessibleProxy, zbus};
+use futures_util::StreamExt;
+use gtk::{gdk, prelude::*};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::BufWriter,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Event classes the registry knows, and whether the monitor starts with them registered.
+const CLASSES: &[(&str, bool)] = &[
+    ("object", true),
+    ("window", true),
+    ("focus", true),
+    ("document", false),
+    ("terminal", false),
+    ("mouse", false),
+    ("keyboard", false),
+];
+
+/// Oldest events are dropped past this.
+const MAX_EVENTS: u32 = 10_000;
+
+/// Resolved source labels kept before the cache is reset.
+const MAX_SOURCES: usize = 5_000;
+
+/// Events waiting to be resolved before new ones are dropped.
+const QUEUE_LEN: usize = 1_000;
+
+/// Events before the selected one that stay outlined on the canvas.
+const TRAIL: u32 = 4;
+
+/// An event as logged, with its application and source object resolved when it arrived.
+#[derive(Clone, Debug)]
+pub struct Logged {
+    pub event: Event,
+    pub app: String,
+    pub source: String,
+    /// Extents of the source in the loaded tree, if it was loaded.
+    pub extents: Option<gdk::Rectangle>,
+}
+
+/// Page logging the AT-SPI events of the chosen classes from every application.
+#[derive(Clone)]
+pub struct Monitor {
+    pub widget: gtk::Box,
+    /// Scrubber over the shown events, visible while replaying. It lives outside `widget` so it
+    /// can sit under the overview, where the marks for the scrubbed events are drawn.
+    pub timeline: gtk::Box,
+    conn: zbus::Connection,
+    overview: Overview,
+    store: gio::ListStore,
+    select: gtk::SingleSelection,
+    paused: Rc<Cell<bool>>,
+    /// Showing a recording instead of live events.
+    replaying: Rc<Cell<bool>>,
+    recording: Rc<RefCell<Option<BufWriter<File>>>>,
+    enabled: Rc<RefCell<HashSet<&'static str>>>,
+    subs: Rc<RefCell<HashMap<&'static str, Subscription>>>,
+    listener: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+    /// Events dropped because resolving fell too far behind, shown in `overflow`.
+    dropped: Rc<Cell<usize>>,
+    overflow: gtk::Label,
+    apps: Rc<RefCell<HashMap<String, String>>>,
+    sources: Rc<RefCell<HashMap<ObjectKey, String>>>,
+}
+
+impl Monitor {
+    pub fn new(conn: &zbus::Connection, overview: &Overview) -> Self {
+        let store = gio::ListStore::new(glib::BoxedAnyObject::static_type());
+        let app_filter = gtk::SearchEntry::builder()
+            .placeholder_text("Application")
+            .build();
+        let type_filter = gtk::SearchEntry::builder()
+            .placeholder_text("Event type")
+            .build();
+        let node_filter = gtk::CheckButton::with_label("Picked node only");
+        let filter = {
+            let (app_filter, type_filter) = (app_filter.clone(), type_filter.clone());
+            let node_filter = node_filter.clone();
+            let overview = overview.clone();
+            gtk::CustomFilter::new(move |obj| {
+                let obj = obj.downcast_ref::<glib::BoxedAnyObject>().unwrap();
+                let logged = obj.borrow::<Logged>();
+                let app = app_filter.text().to_lowercase();
+                if !app.is_empty()
+                    && !logged.app.to_lowercase().contains(&app)
+                    && !logged.event.sender.contains(&app)
+                {
+                    return false;
+                }
+                let kind = type_filter.text().to_lowercase();
+                if !kind.is_empty() && !logged.event.name().contains(&kind) {
+                    return false;
+                }
+                if node_filter.is_active() {
+                    let picked = overview.picked();
+                    let is_picked = picked.map_or(false, |node| {
+                        node.borrow().is(&logged.event.sender, &logged.event.path)
+                    });
+                    if !is_picked {
+                        return false;
+                    }
+                }
+                true
+            })
+        };
+        let refilter = {
+            let filter = filter.clone();
+            move || filter.changed(gtk::FilterChange::Different)
+        };
+        {
+            let refilter = refilter.clone();
+            app_filter.connect_search_changed(move |_| refilter());
+        }
+        {
+            let refilter = refilter.clone();
+            type_filter.connect_search_changed(move |_| refilter());
+        }
+        {
+            let refilter = refilter.clone();
+            node_filter.connect_toggled(move |_| refilter());
+        }
+        overview.connect_node_picked(move |_, _| refilter());
+
+        let model = gtk::FilterListModel::new(Some(store.clone()), Some(filter));
+        let select = gtk::SingleSelection::builder()
+            .model(&model)
+            .autoselect(false)
+            .can_unselect(true)
+            .build();
+        let view = gtk::ColumnView::new(Some(select.clone()));
+        view.add_css_class("data-table");
+        let columns: [(&str, fn(&Logged) -> String); 7] = [
+            ("Time", |l| format_time(l.event.time)),
+            ("Application", |l| l.app.clone()),
+            ("Event", |l| l.event.name()),
+            ("Source", |l| l.source.clone()),
+            ("detail1", |l| l.event.detail1.to_string()),
+            ("detail2", |l| l.event.detail2.to_string()),
+            ("any_data", |l| l.event.any_data_text()),
+        ];
+        for (title, text) in columns {
+            view.append_column(&column(title, text));
+        }
+        let scroll = gtk::ScrolledWindow::builder()
+            .child(&view)
+            .vexpand(true)
+            .hexpand(true)
+            .build();
+
+        let monitor = Self {
+            widget: gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .build(),
+            timeline: gtk::Box::builder()
+                .orientation(gtk::Orientation::Horizontal)
+                .spacing(6)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(6)
+                .margin_end(6)
+                .visible(false)
+                .build(),
+            conn: conn.clone(),
+            overview: overview.clone(),
+            store,
+            select,
+            paused: Default::default(),
+            replaying: Default::default(),
+            recording: Default::default(),
+            enabled: Default::default(),
+            subs: Default::default(),
+            listener: Default::default(),
+            dropped: Default::default(),
+            overflow: gtk::Label::builder()
+                .visible(false)
+                .css_classes(["warning"])
+                .build(),
+            apps: Default::default(),
+            sources: Default::default(),
+        };
+
+        let classes = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(6)
+            .build();
+        for &(class, active) in CLASSES {
+            let check = gtk::CheckButton::with_label(class);
+            let m = monitor.clone();
+            check.connect_toggled(move |check| m.set_class(class, check.is_active()));
+            check.set_active(active);
+            classes.append(&check);
+        }
+        let pause = gtk::ToggleButton::builder()
+            .icon_name("media-playback-pause-symbolic")
+            .tooltip_text("Pause")
+            .build();
+        {
+            let paused = monitor.paused.clone();
+            pause.connect_toggled(move |pause| paused.set(pause.is_active()));
+        }
+        let clear = gtk::Button::builder()
+            .icon_name("edit-clear-all-symbolic")
+            .tooltip_text("Clear")
+            .build();
+        {
+            let m = monitor.clone();
+            clear.connect_clicked(move |_| m.end_replay());
+        }
+        let record = gtk::ToggleButton::builder()
+            .icon_name("media-record-symbolic")
+            .tooltip_text("Record to file")
+            .build();
+        {
+            let m = monitor.clone();
+            record.connect_toggled(move |record| m.set_recording(record));
+        }
+        let open = gtk::Button::builder()
+            .icon_name("document-open-symbolic")
+            .tooltip_text("Open recording")
+            .build();
+        {
+            let m = monitor.clone();
+            open.connect_clicked(move |open| m.open(open));
+        }
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(6)
+            .margin_end(6)
+            .build();
+        toolbar.append(&classes);
+        toolbar.append(&gtk::Separator::new(gtk::Orientation::Vertical));
+        toolbar.append(&app_filter);
+        toolbar.append(&type_filter);
+        toolbar.append(&node_filter);
+        toolbar.append(&gtk::Box::builder().hexpand(true).build());
+        toolbar.append(&monitor.overflow);
+        toolbar.append(&pause);
+        toolbar.append(&clear);
+        toolbar.append(&record);
+        toolbar.append(&open);
+        monitor.widget.append(&toolbar);
+        monitor.widget.append(&scroll);
+        monitor.build_timeline(&model);
+        {
+            let m = monitor.clone();
+            monitor
+                .select
+                .connect_selected_notify(move |_| m.mark_selected());
+        }
+
+        {
+            let m = monitor.clone();
+            monitor.widget.connect_map(move |_| m.update_capture());
+        }
+        {
+            let m = monitor.clone();
+            monitor.widget.connect_unmap(move |_| m.update_capture());
+        }
+        monitor
+    }
+    /// Events are only registered while the page is shown or a recording is running.
+    fn capturing(&self) -> bool {
+        self.widget.is_mapped() || self.recording.borrow().is_some()
+    }
+    /// Starts or stops registering and logging events to match `capturing`.
+    fn update_capture(&self) {
+        if !self.capturing() {
+            self.subs.borrow_mut().clear();
+            if let Some(listener) = self.listener.take() {
+                listener.abort();
+            }
+            return;
+        }
+        let enabled = self.enabled.borrow().iter().copied().collect::<Vec<_>>();
+        for class in enabled {
+            if !self.subs.borrow().contains_key(class) {
+                self.subscribe(class);
+            }
+        }
+        if self.listener.borrow().is_some() {
+            return;
+        }
+        let (paused, replaying) = (self.paused.clone(), self.replaying.clone());
+        let (enabled, recording) = (self.enabled.clone(), self.recording.clone());
+        let dropped = self.dropped.clone();
+        let mut events = crate::events::bounded(&self.conn, QUEUE_LEN, dropped, move |event| {
+            // a replay hides live events, but a recording still needs them
+            !paused.get()
+                && (!replaying.get() || recording.borrow().is_some())
+                && enabled
+                    .borrow()
+                    .contains(event.class.to_lowercase().as_str())
+        });
+        let m = self.clone();
+        let listener = glib::MainContext::default().spawn_local(async move {
+            // resolving awaits calls, so it happens behind the queue rather than in the reader
+            while let Some(event) = events.next().await {
+                let logged = m.resolve(&m.conn, &m.overview, event).await;
+                m.push(logged);
+                m.show_dropped();
+            }
+        });
+        self.listener.replace(Some(listener));
+    }
+    /// Enables or disables one event class, registering it if events are being captured.
+    fn set_class(&self, class: &'static str, active: bool) {
+        if !active {
+            self.enabled.borrow_mut().remove(class);
+            self.subs.borrow_mut().remove(class);
+            return;
+        }
+        self.enabled.borrow_mut().insert(class);
+        if self.capturing() {
+            self.subscribe(class);
+        }
+    }
+    fn subscribe(&self, class: &'static str) {
+        let monitor = self.clone();
+        glib::MainContext::default().spawn_local(async move {
+            match Subscription::new(&monitor.conn, &[&format!("{class}:")]).await {
+                // the class or the capture may have been switched off again while registering
+                Ok(sub) if monitor.enabled.borrow().contains(class) && monitor.capturing() => {
+                    monitor.subs.borrow_mut().insert(class, sub);
+                }
+                Ok(_) => {}
+                Err(err) => log::warn!("Failed to register {class} events: {err}"),
+            }
+        });
+    }
+    fn push(&self, logged: Logged) {
+        let mut recording = self.recording.borrow_mut();
+        let mut stopped = false;
+        if let Some(out) = recording.as_mut() {
+            if let Err(err) = crate::recording::write(out, &logged) {
+                crate::toast(&self.widget, &format!("Recording stopped: {err}"));
+                recording.take();
+                stopped = true;
+            }
+        }
+        drop(recording);
+        if stopped {
+            self.update_capture();
+        }
+        if self.replaying.get() {
+            return;
+        }
+        self.store.append(&glib::BoxedAnyObject::new(logged));
+        let excess = self.store.n_items().saturating_sub(MAX_EVENTS);
+        if excess > 0 {
+            self.store.splice(0, excess, &[] as &[glib::Object]);
+        }
+    }
+    fn show_dropped(&self) {
+        let dropped = self.dropped.get();
+        self.overflow.set_visible(dropped > 0);
+        self.overflow
+            .set_label(&format!("{dropped} events dropped, too many to keep up"));
+    }
+    /// Looks up the application name and source object, preferring the loaded tree.
+    async fn resolve(&self, conn: &zbus::Connection, overview: &Overview, event: Event) -> Logged {
+        let cached = self.apps.borrow().get(&event.sender).cloned();
+        let app = match cached {
+            Some(app) => app,
+            None => {
+                let res: zbus::Result<String> = async {
+                    AccessibleProxy::builder(conn)
+                        .destination(event.sender.clone())?
+                        .path("/org/a11y/atspi/accessible/root")?
+                        .build()
+                        .await?
+                        .name()
+                        .await
+                }
+                .await;
+                let app = res.unwrap_or_else(|_| event.sender.clone());
+                self.apps
+                    .borrow_mut()
+                    .insert(event.sender.clone(), app.clone());
+                app
+            }
+        };
+        let key = (event.sender.clone(), event.path.clone());
+        let node = overview.find(&event.sender, &event.path);
+        let cached = node
+            .as_ref()
+            .map(|node| row_label(&node.borrow()))
+            .or_else(|| self.sources.borrow().get(&key).cloned());
+        let source = match cached {
+            Some(source) => source,
+            None => {
+                let res: zbus::Result<String> = async {
+                    let proxy = AccessibleProxy::builder(conn)
+                        .destination(event.sender.clone())?
+                        .path(event.path.clone())?
+                        .cache_properties(zbus::CacheProperties::No)
+                        .build()
+                        .await?;
+                    let role = proxy.get_role().await?;
+                    let name = proxy.name().await?;
+                    Ok(if name.is_empty() {
+                        role.name().to_owned()
+                    } else {
+                        format!("{} “{name}”", role.name())
+                    })
+                }
+                .await;
+                let source = res.unwrap_or_else(|_| event.path.to_string());
+                let mut sources = self.sources.borrow_mut();
+                if sources.len() >= MAX_SOURCES {
+                    sources.clear();
+                }
+                sources.insert(key, source.clone());
+                source
+            }
+        };
+        let extents = node.map(|node| node.borrow().extents);
+        Logged {
+            event,
+            app,
+            source,
+            extents,
+        }
+    }
+    fn set_recording(&self, button: &gtk::ToggleButton) {
+        if !button.is_active() {
+            if let Some(mut out) = self.recording.take() {
+                if let Err(err) = std::io::Write::flush(&mut out) {
+                    crate::toast(button, &format!("Failed to save recording: {err}"));
+                }
+            }
+            self.update_capture();
+            return;
+        }
+        let dialog = gtk::FileDialog::builder()
+            .title("Record Events")
+            .initial_name("events.jsonl")
+            .build();
+        let window = button.root().and_downcast::<gtk::Window>();
+        let monitor = self.clone();
+        let button = button.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let path = match dialog.save_future(window.as_ref()).await {
+                Ok(file) => file.path(),
+                Err(_) => None,
+            };
+            let Some(path) = path else {
+                button.set_active(false);
+                return;
+            };
+            match File::create(&path) {
+                Ok(file) => {
+                    monitor.recording.replace(Some(BufWriter::new(file)));
+                    monitor.update_capture();
+                }
+                Err(err) => {
+                    crate::toast(&button, &format!("{}: {err}", path.display()));
+                    button.set_active(false);
+                }
+            }
+        });
+    }
+    /// Replaces the log with a recording until the timeline is closed.
+    fn open(&self, button: &gtk::Button) {
+        let dialog = gtk::FileDialog::builder().title("Open Recording").build();
+        let window = button.root().and_downcast::<gtk::Window>();
+        let monitor = self.clone();
+        crate::spawn_fut(button, async move {
+            let Ok(file) = dialog.open_future(window.as_ref()).await else {
+                return Ok(());
+            };
+            let Some(path) = file.path() else {
+                return Ok(());
+            };
+            let input = std::io::BufReader::new(File::open(&path)?);
+            let events = crate::recording::read(input)?;
+            monitor.replaying.set(true);
+            let items = events
+                .into_iter()
+                .map(|logged| glib::BoxedAnyObject::new(logged).upcast())
+                .collect::<Vec<glib::Object>>();
+            monitor.store.splice(0, monitor.store.n_items(), &items);
+            Ok(())
+        });
+    }
+    /// Fills `timeline`.
+    fn build_timeline(&self, model: &gtk::FilterListModel) {
+        let scale = gtk::Scale::with_range(gtk::Orientation::Horizontal, 0., 1., 1.);
+        scale.set_hexpand(true);
+        scale.set_digits(0);
+        let position = gtk::Label::builder()
+            .width_chars(24)
+            .xalign(1.)
+            .css_classes(["monospace", "caption"])
+            .build();
+        let close = gtk::Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Back to live events")
+            .build();
+        let bar = &self.timeline;
+        bar.append(&scale);
+        bar.append(&position);
+        bar.append(&close);
+        {
+            let scale = scale.clone();
+            let replaying = self.replaying.clone();
+            let bar = bar.clone();
+            model.connect_items_changed(move |model, _, _, _| {
+                bar.set_visible(replaying.get());
+                scale.set_range(0., model.n_items().saturating_sub(1).max(1) as f64);
+            });
+        }
+        {
+            let select = self.select.clone();
+            scale.connect_value_changed(move |scale| {
+                select.set_selected(scale.value() as u32);
+            });
+        }
+        {
+            let monitor = self.clone();
+            self.select.connect_selected_notify(move |select| {
+                let Some(obj) = select.selected_item() else {
+                    return;
+                };
+                let obj = obj.downcast::<glib::BoxedAnyObject>().unwrap();
+                let logged = obj.borrow::<Logged>();
+                let first = monitor
+                    .store
+                    .item(0)
+                    .and_downcast::<glib::BoxedAnyObject>()
+                    .map_or(logged.event.time, |first| {
+                        first.borrow::<Logged>().event.time
+                    });
+                let offset = crate::recording::offset(first, &logged);
+                position.set_label(&format!(
+                    "+{:.3}s  {}",
+                    offset.as_secs_f64(),
+                    format_time(logged.event.time)
+                ));
+                scale.set_value(select.selected() as f64);
+            });
+        }
+        {
+            let monitor = self.clone();
+            close.connect_clicked(move |_| monitor.end_replay());
+        }
+    }
+    /// Empties the log, going back to live events if a recording was shown.
+    fn end_replay(&self) {
+        self.replaying.set(false);
+        self.timeline.set_visible(false);
+        self.store.remove_all();
+        self.dropped.set(0);
+        self.show_dropped();
+        self.overview.clear_marks("monitor:");
+    }
+    /// Outlines the source of the selected event, and more faintly those of the few before it.
+    fn mark_selected(&self) {
+        let pos = self.select.selected();
+        let model = self.select.model().unwrap();
+        if pos == gtk::INVALID_LIST_POSITION {
+            self.overview.clear_marks("monitor:");
+            return;
+        }
+        let mut marks = Vec::new();
+        for (age, index) in (pos.saturating_sub(TRAIL)..=pos).rev().enumerate() {
+            let Some(obj) = model.item(index).and_downcast::<glib::BoxedAnyObject>() else {
+                continue;
+            };
+            let logged = obj.borrow::<Logged>();
+            let extents = self
+                .overview
+                .find(&logged.event.sender, &logged.event.path)
+                .map(|node| node.borrow().extents)
+                .or(logged.extents);
+            let Some(extents) = extents else {
+                continue;
+            };
+            let mark = if age == 0 {
+                Mark::new(extents, gdk::RGBA::new(0.9, 0.1, 0.1, 1.)).filled()
+            } else {
+                let alpha = 1. - age as f32 / (TRAIL + 1) as f32;
+                Mark::new(extents, gdk::RGBA::new(1., 0.6, 0., alpha))
+            };
+            marks.push(mark);
+        }
+        self.overview.set_marks("monitor:events", marks);
+    }
+}
+
+fn column(title: &str, text: fn(&Logged) -> String) -> gtk::ColumnViewColumn {
+    let factory = gtk::SignalListItemFactory::new();
+    factory.connect_setup(|_, obj| {
+        let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+        item.set_child(Some(
+            &gtk::Label::builder()
+                .xalign(0.)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .max_width_chars(40)
+                .build(),
+        ));
+    });
+    factory.connect_bind(move |_, obj| {
+        let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+        let logged = item
+            .item()
+            .unwrap()
+            .downcast::<glib::BoxedAnyObject>()
+            .unwrap();
+        let text = text(&logged.borrow::<Logged>());
+        let label = item.child().unwrap().downcast::<gtk::Label>().unwrap();
+        label.set_tooltip_text(Some(&text));
+        label.set_label(&text);
+    });
+    gtk::ColumnViewColumn::builder()
+        .title(title)
+        .factory(&factory)
+        .resizable(true)
+        .build()
+}
+
+/// Local wall-clock time with milliseconds.
+pub fn format_time(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    glib::DateTime::from_unix_local(since.as_secs() as i64)
+        .and_then(|t| t.format("%H:%M:%S"))
+        .map(|t| format!("{t}.{:03}", since.subsec_millis()))
+        .unwrap_or_default()
+}
@@ -0,0 +1,425 @@
+use crate::loader::Loader;
+use atspi::{
+    accessible::AccessibleProxy,
+    zbus::{self, zvariant::OwnedObjectPath},
+};
+use gtk::{gdk, prelude::*};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+pub type ObjectKey = (String, OwnedObjectPath);
+
+/// Snapshot of an application's `org.a11y.atspi.Cache`, used to skip per-node round trips.
+#[derive(Default)]
+pub struct Cache {
+    items: HashMap<ObjectKey, atspi::cache::CacheItem>,
+    children: HashMap<ObjectKey, Vec<ObjectKey>>,
+    saved_calls: Cell<usize>,
+    /// Calls that still went to the application during the load, and their total duration.
+    round_trips: Cell<(usize, Duration)>,
+}
+
+impl Cache {
+    pub async fn load(conn: &zbus::Connection, dest: &str) -> zbus::Result<Self> {
+        let proxy = atspi::cache::CacheProxy::builder(conn)
+            .destination(dest.to_owned())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let mut cache = Self::default();
+        let mut children = HashMap::<ObjectKey, Vec<(i32, ObjectKey)>>::new();
+        for item in proxy.get_items().await? {
+            children
+                .entry(item.parent.clone())
+                .or_default()
+                .push((item.index, item.object.clone()));
+            cache.items.insert(item.object.clone(), item);
+        }
+        for (parent, mut list) in children {
+            list.sort_by_key(|(index, _)| *index);
+            cache
+                .children
+                .insert(parent, list.into_iter().map(|(_, key)| key).collect());
+        }
+        Ok(cache)
+    }
+    /// D-Bus round trips answered by the cache instead of the application.
+    pub fn saved_calls(&self) -> usize {
+        self.saved_calls.get()
+    }
+    /// Estimated time the saved calls would have taken, from the average of the calls that were
+    /// made. This is summed call time; with concurrent loading less wall-clock time was saved.
+    pub fn saved_time(&self) -> Option<Duration> {
+        let (calls, total) = self.round_trips.get();
+        if calls == 0 {
+            return None;
+        }
+        Some(total / calls as u32 * self.saved_calls() as u32)
+    }
+    /// Adds an uncached call to the average used by `saved_time`.
+    pub fn record_round_trip(&self, time: Duration) {
+        let (calls, total) = self.round_trips.get();
+        self.round_trips.set((calls + 1, total + time));
+    }
+    fn item(&self, dest: &str, path: &OwnedObjectPath) -> Option<&atspi::cache::CacheItem> {
+        self.items.get(&(dest.to_owned(), path.clone()))
+    }
+    fn save_calls(&self, n: usize) {
+        self.saved_calls.set(self.saved_calls.get() + n);
+    }
+    /// Only trusted when every child the item advertises was also reported.
+    pub fn children(&self, dest: &str, path: &OwnedObjectPath) -> Option<Vec<ObjectKey>> {
+        let item = self.item(dest, path)?;
+        let key = (dest.to_owned(), path.clone());
+        let children = self.children.get(&key).cloned().unwrap_or_default();
+        if children.len() != item.children.max(0) as usize {
+            return None;
+        }
+        self.save_calls(1);
+        Some(children)
+    }
+}
+
+/// Why the children of a node were not (all) loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// The object was already reached elsewhere in the walk.
+    Cycle,
+    Depth,
+    Count,
+}
+
+impl std::fmt::Display for Truncation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Cycle => "already shown elsewhere",
+            Self::Depth => "depth limit reached",
+            Self::Count => "node limit reached",
+        })
+    }
+}
+
+/// What the Image interface reports, for nodes implementing it.
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub description: String,
+    /// Can differ from the component extents.
+    pub extents: gdk::Rectangle,
+}
+
+#[derive(Debug)]
+pub struct Node {
+    /// Connection the object was loaded over, kept for follow-up queries.
+    pub conn: zbus::Connection,
+    pub dest: String,
+    pub path: OwnedObjectPath,
+    pub extents: gdk::Rectangle,
+    pub name: String,
+    pub role: atspi::accessible::Role,
+    pub interfaces: atspi::InterfaceSet,
+    pub states: atspi::StateSet,
+    pub image: Option<Image>,
+    /// Calls that failed while loading this node.
+    pub error: Option<String>,
+    pub truncated: Option<Truncation>,
+    pub children: Vec<Rc<RefCell<Node>>>,
+    /// Mirrors `children` as `BoxedAnyObject`s for list widgets.
+    pub model: gio::ListStore,
+}
+
+impl Node {
+    /// A node with nothing loaded yet.
+    pub fn empty(conn: &zbus::Connection, dest: String, path: OwnedObjectPath) -> Self {
+        Self {
+            conn: conn.clone(),
+            dest,
+            path,
+            extents: gdk::Rectangle::new(0, 0, 0, 0),
+            name: String::new(),
+            role: atspi::accessible::Role::Invalid,
+            interfaces: atspi::InterfaceSet::empty(),
+            states: atspi::StateSet::empty(),
+            image: None,
+            error: None,
+            truncated: None,
+            children: Vec::new(),
+            model: gio::ListStore::new(glib::BoxedAnyObject::static_type()),
+        }
+    }
+    /// Failed calls are recorded in `error` and leave the defaults from `empty` in place.
+    pub async fn new(proxy: AccessibleProxy<'static>) -> Self {
+        let mut node = Self::empty(
+            proxy.connection(),
+            proxy.destination().to_string(),
+            proxy.path().to_owned().into(),
+        );
+        if let Some(ifaces) = node.record("GetInterfaces", proxy.get_interfaces().await) {
+            node.interfaces = ifaces;
+        }
+        if let Some(role) = node.record("GetRole", proxy.get_role().await) {
+            node.role = role;
+        }
+        if let Some(name) = node.record("Name", proxy.name().await) {
+            node.name = name;
+        }
+        if let Some(states) = node.record("GetState", proxy.get_state().await) {
+            node.states = states;
+        }
+        let extents = Self::extents(&proxy, node.interfaces).await;
+        if let Some(extents) = node.record("GetExtents", extents) {
+            node.extents = extents;
+        }
+        node.load_image(&proxy).await;
+        node
+    }
+    /// Like `new`, but takes everything except the extents from `cache` when it has the object.
+    pub async fn with_cache(proxy: AccessibleProxy<'static>, cache: Option<&Cache>) -> Self {
+        let dest = proxy.destination().to_string();
+        let path = OwnedObjectPath::from(proxy.path().to_owned());
+        let Some(cache) = cache else {
+            return Self::new(proxy).await;
+        };
+        let Some(item) = cache.item(&dest, &path) else {
+            return Self::new(proxy).await;
+        };
+        // interfaces, role, name and states
+        cache.save_calls(4);
+        let mut node = Self::empty(proxy.connection(), dest, path);
+        // `short_name` is the accessible name, `name` the description
+        node.name = item.short_name.clone();
+        node.role = item.role;
+        node.interfaces = item.ifaces;
+        node.states = item.states;
+        let start = Instant::now();
+        let extents = Self::extents(&proxy, item.ifaces).await;
+        if item.ifaces.contains(atspi::Interface::Component) {
+            cache.record_round_trip(start.elapsed());
+        }
+        if let Some(extents) = node.record("GetExtents", extents) {
+            node.extents = extents;
+        }
+        node.load_image(&proxy).await;
+        node
+    }
+    /// Appends a failed call to `error`, passing successful results through.
+    pub fn record<T, E: std::fmt::Display>(&mut self, call: &str, res: Result<T, E>) -> Option<T> {
+        match res {
+            Ok(value) => Some(value),
+            Err(err) => {
+                let msg = format!("{call}: {err}");
+                match &mut self.error {
+                    Some(error) => {
+                        error.push_str("; ");
+                        error.push_str(&msg);
+                    }
+                    None => self.error = Some(msg),
+                }
+                None
+            }
+        }
+    }
+    async fn extents(
+        proxy: &AccessibleProxy<'static>,
+        ifaces: atspi::InterfaceSet,
+    ) -> anyhow::Result<gdk::Rectangle> {
+        if !ifaces.contains(atspi::Interface::Component) {
+            return Ok(gdk::Rectangle::new(0, 0, 0, 0));
+        }
+        let component = atspi::component::ComponentProxy::builder(proxy.connection())
+            .destination(proxy.destination())?
+            .path(proxy.path())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        let (x, y, w, h) = component.get_extents(atspi::CoordType::Window).await?;
+        Ok(gdk::Rectangle::new(x, y, w, h))
+    }
+    /// Only queries objects listing the Image interface, which are few.
+    async fn load_image(&mut self, proxy: &AccessibleProxy<'static>) {
+        if !self.interfaces.contains(atspi::Interface::Image) {
+            return;
+        }
+        let res: zbus::Result<Image> = async {
+            let image = atspi::image::ImageProxy::builder(proxy.connection())
+                .destination(proxy.destination())?
+                .path(proxy.path())?
+                .cache_properties(zbus::CacheProperties::No)
+                .build()
+                .await?;
+            let (x, y, w, h) = image.get_image_extents(atspi::CoordType::Window).await?;
+            Ok(Image {
+                description: image.image_description().await?,
+                extents: gdk::Rectangle::new(x, y, w, h),
+            })
+        }
+        .await;
+        self.image = match res {
+            // a toolkit bug worth reporting, so say what is wrong rather than the bare D-Bus error
+            Err(err) if is_missing_interface(&err) => self.record(
+                "Image",
+                Err::<Image, _>(format!("listed in Interfaces but not implemented ({err})")),
+            ),
+            res => self.record("Image", res),
+        };
+    }
+    /// An image nothing describes, neither its name nor its image description.
+    pub fn is_unlabeled_image(&self) -> bool {
+        self.image.as_ref().map_or(false, |image| {
+            self.name.is_empty() && image.description.is_empty()
+        })
+    }
+    /// Re-reads everything but the children from the remote object.
+    pub async fn refresh(node: &Rc<RefCell<Node>>) -> anyhow::Result<()> {
+        let proxy = node.borrow().accessible();
+        let fresh = Node::new(proxy.await?).await;
+        let mut node = node.borrow_mut();
+        node.extents = fresh.extents;
+        node.name = fresh.name;
+        node.role = fresh.role;
+        node.interfaces = fresh.interfaces;
+        node.states = fresh.states;
+        node.image = fresh.image;
+        node.error = fresh.error;
+        Ok(())
+    }
+    /// Brings the children in line with the remote object, keeping nodes that are still present
+    /// and loading the subtrees of new ones with `loader`. `depth` is the level of `node` below
+    /// the root.
+    pub async fn sync_children(
+        node: &Rc<RefCell<Node>>,
+        depth: usize,
+        loader: &Loader,
+    ) -> anyhow::Result<()> {
+        let proxy = node.borrow().accessible();
+        let remote = proxy.await?.get_children().await?;
+        let stale = node
+            .borrow()
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| !remote.iter().any(|(d, p)| child.borrow().is(d, p)))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        for index in stale.into_iter().rev() {
+            let child = Self::remove_child(node, index);
+            loader.forget(&child);
+        }
+        for (index, (dest, path)) in remote.into_iter().enumerate() {
+            let existing = node
+                .borrow()
+                .children
+                .iter()
+                .position(|child| child.borrow().is(&dest, &path));
+            match existing {
+                Some(pos) if pos == index => {}
+                Some(pos) => {
+                    let child = Self::remove_child(node, pos);
+                    Self::insert_child(node, index, child);
+                }
+                None => {
+                    if let Some(child) = loader.child(node, depth, (dest, path)).await {
+                        Self::insert_child(node, index, child);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    pub fn insert_child(node: &Rc<RefCell<Node>>, index: usize, child: Rc<RefCell<Node>>) {
+        let model = node.borrow().model.clone();
+        let index = index.min(node.borrow().children.len());
+        node.borrow_mut().children.insert(index, child.clone());
+        model.insert(index as u32, &glib::BoxedAnyObject::new(child));
+    }
+    fn remove_child(node: &Rc<RefCell<Node>>, index: usize) -> Rc<RefCell<Node>> {
+        let model = node.borrow().model.clone();
+        let child = node.borrow_mut().children.remove(index);
+        model.remove(index as u32);
+        child
+    }
+    pub fn is(&self, dest: &str, path: &str) -> bool {
+        self.dest == dest && self.path.as_str() == path
+    }
+    pub fn find(node: &Rc<RefCell<Node>>, dest: &str, path: &str) -> Option<Rc<RefCell<Node>>> {
+        if node.borrow().is(dest, path) {
+            return Some(node.clone());
+        }
+        node.borrow()
+            .children
+            .iter()
+            .find_map(|child| Self::find(child, dest, path))
+    }
+    /// Builds a proxy for any interface of the object without keeping `self` borrowed across the
+    /// await. Properties are not cached, so every read goes to the application.
+    pub fn proxy<P>(&self) -> impl std::future::Future<Output = zbus::Result<P>>
+    where
+        P: From<zbus::Proxy<'static>> + zbus::ProxyDefault + 'static,
+    {
+        let builder = zbus::ProxyBuilder::<P>::new(&self.conn)
+            .destination(self.dest.clone())
+            .and_then(|b| b.path(self.path.clone()))
+            .map(|b| b.cache_properties(zbus::CacheProperties::No));
+        async move { builder?.build().await }
+    }
+    pub fn accessible(
+        &self,
+    ) -> impl std::future::Future<Output = zbus::Result<AccessibleProxy<'static>>> {
+        self.proxy()
+    }
+    pub fn from_item(obj: &glib::Object) -> Rc<RefCell<Node>> {
+        obj.downcast_ref::<glib::BoxedAnyObject>()
+            .unwrap()
+            .borrow::<Rc<RefCell<Node>>>()
+            .clone()
+    }
+    /// Every node in the subtree with a recorded error.
+    pub fn errors(node: &Rc<RefCell<Node>>, out: &mut Vec<Rc<RefCell<Node>>>) {
+        if node.borrow().error.is_some() {
+            out.push(node.clone());
+        }
+        for child in &node.borrow().children {
+            Self::errors(child, out);
+        }
+    }
+    pub fn pick(node: &Rc<RefCell<Node>>, x: i32, y: i32) -> Rc<RefCell<Node>> {
+        for child in &node.borrow().children {
+            if child.borrow().extents.contains_point(x, y) {
+                return Self::pick(child, x, y);
+            }
+        }
+        node.clone()
+    }
+    /// Child indices leading from `node` down to `target`.
+    pub fn index_path(node: &Rc<RefCell<Node>>, target: &Rc<RefCell<Node>>) -> Option<Vec<u32>> {
+        if Rc::ptr_eq(node, target) {
+            return Some(Vec::new());
+        }
+        for (index, child) in node.borrow().children.iter().enumerate() {
+            if let Some(mut path) = Self::index_path(child, target) {
+                path.insert(0, index as u32);
+                return Some(path);
+            }
+        }
+        None
+    }
+}
+
+/// Whether a call failed because the object does not implement the interface at all.
+fn is_missing_interface(err: &zbus::Error) -> bool {
+    use zbus::DBusError;
+    let name = match err {
+        zbus::Error::MethodError(name, _, _) => name.to_string(),
+        zbus::Error::FDO(err) => err.name().to_string(),
+        _ => return false,
+    };
+    matches!(
+        name.as_str(),
+        "org.freedesktop.DBus.Error.UnknownInterface"
+            | "org.freedesktop.DBus.Error.UnknownMethod"
+            | "org.freedesktop.DBus.Error.UnknownProperty"
+    )
+}
@@ -1,8 +1,18 @@
-use atspi::accessible::AccessibleProxy;
+use crate::{
+    events::Event,
+    loader::{Limits, Loader, Progress, Visited},
+    node::{Cache, Node, ObjectKey},
+};
+use atspi::{
+    accessible::{AccessibleProxy, RelationType},
+    zbus::{self, zvariant::OwnedObjectPath},
+};
+use futures_util::StreamExt;
 use glow::HasContext;
 use gtk::{gdk, prelude::*, subclass::prelude::*};
 use std::{
     cell::{Cell, RefCell, RefMut},
+    collections::HashMap,
     num::NonZeroU32,
     rc::Rc,
 };
@@ -21,27 +31,61 @@ impl Overview {
         self.clear();
         let overview = self.clone();
         let handle = super::spawn_fut(self, async move {
-            let node = Node::new(proxy.clone()).await?;
-            overview.imp().node.replace(Some(node));
+            let conn = proxy.connection().clone();
+            let cache = match Cache::load(&conn, proxy.destination()).await {
+                Ok(cache) => Some(Rc::new(cache)),
+                Err(err) => {
+                    log::debug!("No cache for {}: {err}", proxy.destination());
+                    None
+                }
+            };
+            let weak = overview.downgrade();
+            let loader = Loader::new(&conn, cache.clone(), overview.imp().limits.get())
+                .with_visited(overview.imp().visited.clone())
+                .with_progress(move |progress| {
+                    if let Some(overview) = weak.upgrade() {
+                        overview.emit_progress(progress);
+                    }
+                });
+            let key = (
+                proxy.destination().to_string(),
+                proxy.path().to_owned().into(),
+            );
+            let (node, children) = loader.root(&key).await;
+            overview.imp().node.replace(Some(node.clone()));
+            overview
+                .model()
+                .append(&glib::BoxedAnyObject::new(node.clone()));
             overview.queue_draw();
-            for (dest, path) in proxy.get_children().await? {
-                let proxy = atspi::accessible::AccessibleProxy::builder(proxy.connection())
-                    .destination(dest)?
-                    .path(path)?
-                    .build()
-                    .await?;
-                let child = Rc::new(RefCell::new(Node::new(proxy.clone()).await?));
-                overview
-                    .imp()
-                    .node
-                    .borrow_mut()
-                    .as_mut()
-                    .unwrap()
-                    .children
-                    .push(child.clone());
-                Node::fill_children(child, proxy).await?;
-            }
+            loader.fill(&node, 0, children).await;
             overview.imp().handle.replace(None);
+            overview.update_errors();
+            let progress = Progress {
+                finished: true,
+                ..loader.progress()
+            };
+            overview.emit_progress(progress);
+            if let Some((dest, path)) = overview.imp().pending_pick.take() {
+                overview.pick_object(&dest, &path);
+            }
+            let mut msg = format!("Loaded {progress}");
+            if let Some(cache) = &cache {
+                match cache.saved_time() {
+                    Some(time) => msg.push_str(&format!(
+                        ", cache saved about {:.1}s of calls ({} calls)",
+                        time.as_secs_f64(),
+                        cache.saved_calls()
+                    )),
+                    None => msg.push_str(&format!(
+                        ", {} calls answered by the cache",
+                        cache.saved_calls()
+                    )),
+                }
+            }
+            log::info!("{msg}");
+            super::toast(&overview, &msg);
+            let listener = super::spawn_fut(&overview, overview.clone().listen(conn));
+            overview.imp().listener.replace(Some(listener));
             Ok(())
         });
         self.imp().handle.replace(Some(handle));
@@ -49,124 +93,334 @@ impl Overview {
     pub fn clear(&self) {
         if let Some(handle) = self.imp().handle.take() {
             handle.abort();
+            self.emit_progress(Progress {
+                finished: true,
+                ..Default::default()
+            });
         }
+        if let Some(listener) = self.imp().listener.take() {
+            listener.abort();
+        }
+        self.imp().picked.replace(None);
+        self.imp().hovered.replace(None);
         self.imp().node.replace(None);
+        self.imp().relations.replace(None);
+        self.imp().pending_pick.replace(None);
+        self.imp().visited.borrow_mut().clear();
+        self.imp().marks.borrow_mut().clear();
+        self.model().remove_all();
+        self.errors().remove_all();
         self.queue_draw();
     }
-}
-
-#[derive(Debug)]
-struct Node {
-    extents: gdk::Rectangle,
-    name: String,
-    role: atspi::accessible::Role,
-    children: Vec<Rc<RefCell<Node>>>,
-}
-
-impl Node {
-    async fn new(proxy: AccessibleProxy<'static>) -> anyhow::Result<Self> {
-        let ifaces = proxy.get_interfaces().await?;
-        let role = proxy.get_role().await?;
-        let name = proxy.name().await?;
-        let extents = if ifaces.contains(atspi::Interface::Component) {
-            let component = atspi::component::ComponentProxy::builder(proxy.connection())
-                .destination(proxy.destination())?
-                .path(proxy.path())?
-                .build()
-                .await?;
-            let (x, y, w, h) = component.get_extents(atspi::CoordType::Window).await?;
-            gdk::Rectangle::new(x, y, w, h)
-        } else {
-            gdk::Rectangle::new(0, 0, 0, 0)
-        };
-        Ok(Self {
-            extents,
-            name,
-            role,
-            children: Vec::new(),
+    /// List holding the root node, if one is loaded.
+    pub fn model(&self) -> gio::ListStore {
+        self.imp()
+            .model
+            .get_or_init(|| gio::ListStore::new(glib::BoxedAnyObject::static_type()))
+            .clone()
+    }
+    pub fn limits(&self) -> Limits {
+        self.imp().limits.get()
+    }
+    /// Takes effect on the next load.
+    pub fn set_limits(&self, limits: Limits) {
+        self.imp().limits.set(limits);
+    }
+    fn emit_progress(&self, progress: Progress) {
+        self.emit_by_name::<()>("load-progress", &[&glib::BoxedAnyObject::new(progress)]);
+        self.queue_render();
+    }
+    pub fn connect_load_progress<F: Fn(&Self, Progress) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("load-progress", false, move |args| {
+            let overview = args[0].get::<Self>().unwrap();
+            let obj = args[1].get::<glib::BoxedAnyObject>().unwrap();
+            let progress = *obj.borrow::<Progress>();
+            f(&overview, progress);
+            None
         })
     }
-    #[async_recursion::async_recursion(?Send)]
-    async fn fill_children(
-        node: Rc<RefCell<Node>>,
-        proxy: AccessibleProxy<'static>,
-    ) -> anyhow::Result<()> {
-        for (dest, path) in proxy.get_children().await? {
-            let proxy = atspi::accessible::AccessibleProxy::builder(proxy.connection())
-                .destination(dest)?
-                .path(path)?
-                .build()
-                .await?;
-            let child = Rc::new(RefCell::new(Node::new(proxy.clone()).await?));
-            node.borrow_mut().children.push(child.clone());
-            Self::fill_children(child, proxy).await?;
+    /// Nodes with a recorded error, in tree order as loaded. Errors in subtrees that had no errors
+    /// before a live update are appended.
+    pub fn errors(&self) -> gio::ListStore {
+        self.imp()
+            .errors
+            .get_or_init(|| gio::ListStore::new(glib::BoxedAnyObject::static_type()))
+            .clone()
+    }
+    fn update_errors(&self) {
+        let mut nodes = Vec::new();
+        if let Some(root) = self.imp().node.borrow().as_ref() {
+            Node::errors(root, &mut nodes);
         }
-        Ok(())
+        let items = nodes
+            .into_iter()
+            .map(|node| glib::BoxedAnyObject::new(node).upcast())
+            .collect::<Vec<glib::Object>>();
+        let errors = self.errors();
+        errors.splice(0, errors.n_items(), &items);
+    }
+    /// Swaps the errors `before` an update of `node` for the ones in its subtree now, where the
+    /// first of them was, without walking the rest of the tree.
+    fn update_subtree_errors(&self, node: &Rc<RefCell<Node>>, before: &[Rc<RefCell<Node>>]) {
+        let mut after = Vec::new();
+        Node::errors(node, &mut after);
+        if before.is_empty() && after.is_empty() {
+            return;
+        }
+        let errors = self.errors();
+        let mut pos = None;
+        let mut index = 0;
+        while index < errors.n_items() {
+            let item = Node::from_item(&errors.item(index).unwrap());
+            if before.iter().any(|node| Rc::ptr_eq(node, &item)) {
+                pos.get_or_insert(index);
+                errors.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        let items = after
+            .into_iter()
+            .map(|node| glib::BoxedAnyObject::new(node).upcast())
+            .collect::<Vec<glib::Object>>();
+        errors.splice(pos.unwrap_or(errors.n_items()), 0, &items);
+    }
+    pub fn picked(&self) -> Option<Rc<RefCell<Node>>> {
+        self.imp().picked.borrow().clone()
+    }
+    pub fn set_picked(&self, node: Option<Rc<RefCell<Node>>>) {
+        let imp = self.imp();
+        let same = match (&*imp.picked.borrow(), &node) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        if same {
+            return;
+        }
+        imp.picked.replace(node);
+        self.queue_render();
     }
-    fn pick(&self, x: i32, y: i32, func: impl FnOnce(&Node)) {
-        for child in &self.children {
-            let child = child.borrow();
-            if child.extents.contains_point(x, y) {
-                child.pick(x, y, func);
-                return;
+    /// Patches the loaded tree in place as the inspected application reports changes. Events that
+    /// queue up during an update are handled together, once per object.
+    async fn listen(self, conn: zbus::Connection) -> anyhow::Result<()> {
+        let _sub = crate::events::Subscription::new(&conn, LIVE_EVENTS).await?;
+        let dest = match self.imp().node.borrow().as_ref() {
+            Some(root) => root.borrow().dest.clone(),
+            None => return Ok(()),
+        };
+        let mut events = crate::events::queued(&conn, move |event| event.sender == dest);
+        while let Some(event) = events.next().await {
+            let mut dirty = HashMap::<ObjectKey, Dirty>::new();
+            let mut mark = |event: Event| {
+                let flags = dirty.entry((event.sender, event.path)).or_default();
+                match event.member.as_str() {
+                    "ChildrenChanged" => flags.children = true,
+                    _ => flags.props = true,
+                }
+            };
+            mark(event);
+            while let Ok(Some(event)) = events.try_next() {
+                mark(event);
+            }
+            for ((dest, path), flags) in dirty {
+                let Some(node) = self.find(&dest, &path) else {
+                    continue;
+                };
+                let mut before = Vec::new();
+                Node::errors(&node, &mut before);
+                if flags.props {
+                    if let Err(err) = Node::refresh(&node).await {
+                        log::debug!("Failed to refresh {}: {err}", path.as_str());
+                    }
+                }
+                if flags.children {
+                    let depth = self
+                        .imp()
+                        .node
+                        .borrow()
+                        .as_ref()
+                        .and_then(|root| Node::index_path(root, &node))
+                        .map_or(0, |path| path.len());
+                    let loader = Loader::new(&conn, None, self.limits())
+                        .with_visited(self.imp().visited.clone());
+                    if let Err(err) = Node::sync_children(&node, depth, &loader).await {
+                        log::debug!("Failed to sync children of {}: {err}", path.as_str());
+                    }
+                }
+                self.update_subtree_errors(&node, &before);
+                self.node_changed(&node);
             }
         }
-        func(self);
+        Ok(())
+    }
+    fn node_changed(&self, node: &Rc<RefCell<Node>>) {
+        self.emit_by_name::<()>("node-changed", &[&glib::BoxedAnyObject::new(node.clone())]);
+        self.queue_render();
+    }
+    pub fn connect_node_changed<F: Fn(&Self, Rc<RefCell<Node>>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("node-changed", false, move |args| {
+            let overview = args[0].get::<Self>().unwrap();
+            let obj = args[1].get::<glib::Object>().unwrap();
+            f(&overview, Node::from_item(&obj));
+            None
+        })
+    }
+    /// Whether the object was reached while loading the tree, without searching it. Cheap enough
+    /// to drop events for other objects before doing anything else with them.
+    pub fn is_loaded(&self, dest: &str, path: &str) -> bool {
+        let Ok(path) = OwnedObjectPath::try_from(path.to_owned()) else {
+            return false;
+        };
+        self.imp()
+            .visited
+            .borrow()
+            .contains(&(dest.to_owned(), path))
+    }
+    /// The loaded node for an object, if any.
+    pub fn find(&self, dest: &str, path: &str) -> Option<Rc<RefCell<Node>>> {
+        if !self.is_loaded(dest, path) {
+            return None;
+        }
+        let root = self.imp().node.borrow().clone()?;
+        Node::find(&root, dest, path)
+    }
+    /// Picks the loaded node for an object as if it had been clicked. Returns `false` if it is
+    /// not loaded.
+    pub fn pick_object(&self, dest: &str, path: &str) -> bool {
+        let Some(node) = self.find(dest, path) else {
+            return false;
+        };
+        self.set_picked(Some(node.clone()));
+        self.emit_by_name::<()>("node-picked", &[&glib::BoxedAnyObject::new(node)]);
+        true
+    }
+    /// Like `pick_object`, but waits for a load in progress to finish first.
+    pub fn pick_when_loaded(&self, dest: &str, path: &str) {
+        if self.imp().handle.borrow().is_some() {
+            let path = OwnedObjectPath::try_from(path.to_owned());
+            self.imp()
+                .pending_pick
+                .replace(path.ok().map(|path| (dest.to_owned(), path)));
+        } else {
+            self.pick_object(dest, path);
+        }
+    }
+    /// Draws arrows from `source` to the loaded targets of its relations while it is picked.
+    pub fn set_relations(
+        &self,
+        source: &Rc<RefCell<Node>>,
+        relations: Vec<(RelationType, Vec<(String, OwnedObjectPath)>)>,
+    ) {
+        let targets = relations
+            .into_iter()
+            .flat_map(|(kind, targets)| {
+                targets
+                    .into_iter()
+                    .filter_map(move |(dest, path)| Some((kind, self.find(&dest, &path)?)))
+            })
+            .collect::<Vec<_>>();
+        self.imp()
+            .relations
+            .replace(Some((source.clone(), targets)));
+        self.queue_render();
+    }
+    /// Replaces the outlines drawn for `group`.
+    pub fn set_marks(&self, group: &str, marks: Vec<Mark>) {
+        let mut all = self.imp().marks.borrow_mut();
+        if marks.is_empty() {
+            all.remove(group);
+        } else {
+            all.insert(group.to_owned(), marks);
+        }
+        drop(all);
+        self.queue_render();
+    }
+    /// Removes the outlines of every group starting with `prefix`.
+    pub fn clear_marks(&self, prefix: &str) {
+        self.imp()
+            .marks
+            .borrow_mut()
+            .retain(|group, _| !group.starts_with(prefix));
+        self.queue_render();
+    }
+    pub fn connect_node_picked<F: Fn(&Self, Rc<RefCell<Node>>) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_local("node-picked", false, move |args| {
+            let overview = args[0].get::<Self>().unwrap();
+            let obj = args[1].get::<glib::Object>().unwrap();
+            f(&overview, Node::from_item(&obj));
+            None
+        })
     }
 }
 
+/// Updates owed to an object by the events received since it was last updated.
+#[derive(Clone, Copy, Debug, Default)]
+struct Dirty {
+    props: bool,
+    children: bool,
+}
+
+const LIVE_EVENTS: &[&str] = &[
+    "object:children-changed",
+    "object:property-change",
+    "object:bounds-changed",
+    "object:state-changed",
+];
+
 type Canvas = femtovg::Canvas<femtovg::renderer::OpenGl>;
 
 #[derive(Default)]
 pub struct OverviewImp {
     canvas: RefCell<Option<Canvas>>,
     handle: RefCell<Option<glib::JoinHandle<()>>>,
-    node: RefCell<Option<Node>>,
-    popover: Popover,
-    picked: Cell<Option<gdk::Rectangle>>,
-    hovered: Cell<Option<gdk::Rectangle>>,
+    listener: RefCell<Option<glib::JoinHandle<()>>>,
+    limits: Cell<Limits>,
+    node: RefCell<Option<Rc<RefCell<Node>>>>,
+    model: glib::once_cell::unsync::OnceCell<gio::ListStore>,
+    errors: glib::once_cell::unsync::OnceCell<gio::ListStore>,
+    picked: RefCell<Option<Rc<RefCell<Node>>>>,
+    hovered: RefCell<Option<Rc<RefCell<Node>>>>,
+    relations: RefCell<Option<Relations>>,
+    marks: RefCell<HashMap<String, Vec<Mark>>>,
+    pending_pick: RefCell<Option<ObjectKey>>,
+    visited: Visited,
 }
 
-struct Popover {
-    popover: gtk::Popover,
-    name: gtk::Label,
-    role: gtk::Label,
+/// An outline drawn over the tree on behalf of another view, in window coordinates.
+#[derive(Clone, Debug)]
+pub struct Mark {
+    pub rect: gdk::Rectangle,
+    pub color: gdk::RGBA,
+    pub fill: bool,
 }
 
-impl Default for Popover {
-    fn default() -> Self {
-        let popover = gtk::Popover::new();
-        let grid = gtk::Grid::builder()
-            .row_spacing(8)
-            .column_spacing(8)
-            .build();
-        let name = gtk::Label::builder().xalign(0.).selectable(true).build();
-        let role = gtk::Label::builder().xalign(0.).selectable(true).build();
-        grid.attach(
-            &gtk::Label::builder().label("Name").xalign(1.).build(),
-            0,
-            0,
-            1,
-            1,
-        );
-        grid.attach(&name, 1, 0, 1, 1);
-        grid.attach(
-            &gtk::Label::builder().label("Role").xalign(1.).build(),
-            0,
-            1,
-            1,
-            1,
-        );
-        grid.attach(&role, 1, 1, 1, 1);
-        popover.set_child(Some(&grid));
+impl Mark {
+    pub fn new(rect: gdk::Rectangle, color: gdk::RGBA) -> Self {
         Self {
-            popover,
-            name,
-            role,
+            rect,
+            color,
+            fill: false,
         }
     }
+    pub fn filled(mut self) -> Self {
+        self.fill = true;
+        self
+    }
 }
 
+/// A node and the loaded targets of its relations.
+type Relations = (Rc<RefCell<Node>>, Vec<(RelationType, Rc<RefCell<Node>>)>);
+
 impl OverviewImp {
     fn ensure_canvas(&self) -> RefMut<Canvas> {
         let mut canvas = self.canvas.borrow_mut();
@@ -202,21 +456,160 @@ impl OverviewImp {
             r.width() as f32,
             r.height() as f32,
         );
-        canvas.stroke_path(&path, fg);
+        if node.error.is_some() {
+            let warning = femtovg::Color::rgbaf(0.9, 0.6, 0., 1.);
+            let mut stroke = femtovg::Paint::color(warning);
+            stroke.set_line_width(2.);
+            canvas.fill_path(
+                &path,
+                &femtovg::Paint::color(femtovg::Color::rgbaf(0.9, 0.6, 0., 0.2)),
+            );
+            canvas.stroke_path(&path, &stroke);
+        } else {
+            canvas.stroke_path(&path, fg);
+        }
+        if let Some(image) = &node.image {
+            let r = &image.extents;
+            let mut outline = femtovg::Path::new();
+            outline.rect(
+                r.x() as f32 + 1.,
+                r.y() as f32 + 1.,
+                (r.width() as f32 - 2.).max(0.),
+                (r.height() as f32 - 2.).max(0.),
+            );
+            let mut stroke = if node.is_unlabeled_image() {
+                femtovg::Paint::color(femtovg::Color::rgbaf(0.9, 0.1, 0.1, 1.))
+            } else {
+                femtovg::Paint::color(femtovg::Color::rgbaf(0., 0.7, 0.8, 1.))
+            };
+            stroke.set_line_width(1.5);
+            canvas.stroke_path(&outline, &stroke);
+            if node.is_unlabeled_image() {
+                // a cross over images nothing describes
+                let mut cross = femtovg::Path::new();
+                cross.move_to(r.x() as f32, r.y() as f32);
+                cross.line_to((r.x() + r.width()) as f32, (r.y() + r.height()) as f32);
+                cross.move_to((r.x() + r.width()) as f32, r.y() as f32);
+                cross.line_to(r.x() as f32, (r.y() + r.height()) as f32);
+                canvas.stroke_path(&cross, &stroke);
+            }
+        }
+        if node.truncated.is_some() {
+            // marks where the walk stopped short of the real tree
+            let mut marker = femtovg::Path::new();
+            marker.circle(r.x() as f32, r.y() as f32, 4.);
+            canvas.fill_path(
+                &marker,
+                &femtovg::Paint::color(femtovg::Color::rgbaf(0.8, 0., 0.8, 1.)),
+            );
+        }
     }
-    fn pick(&self, x: i32, y: i32, func: impl FnOnce(&Node)) -> bool {
+    fn pick(&self, x: i32, y: i32) -> Option<Rc<RefCell<Node>>> {
         let node = self.node.borrow();
-        if let Some(node) = node.as_ref() {
-            if node.extents.contains_point(x, y) {
-                node.pick(x, y, func);
-                return true;
+        let node = node.as_ref()?;
+        if node.borrow().extents.contains_point(x, y) {
+            Some(Node::pick(node, x, y))
+        } else {
+            None
+        }
+    }
+    fn highlight(&self, node: &Node, canvas: &mut Canvas, paint: &femtovg::Paint) {
+        let r = &node.extents;
+        let mut path = femtovg::Path::new();
+        if r.width() > 0 && r.height() > 0 {
+            path.rect(
+                r.x() as f32,
+                r.y() as f32,
+                r.width() as f32,
+                r.height() as f32,
+            );
+            canvas.fill_path(&path, paint);
+        } else {
+            // zero-sized nodes get a crosshair so they can still be located
+            let (x, y) = (r.x() as f32, r.y() as f32);
+            path.move_to(x - 8., y);
+            path.line_to(x + 8., y);
+            path.move_to(x, y - 8.);
+            path.line_to(x, y + 8.);
+            let mut paint = paint.clone();
+            paint.set_line_width(2.);
+            canvas.stroke_path(&path, &paint);
+        }
+    }
+    /// Arrows from the picked node to its relation targets, colored by relation type.
+    fn draw_relations(&self, canvas: &mut Canvas) {
+        let picked = self.picked.borrow();
+        let relations = self.relations.borrow();
+        let (Some(picked), Some((source, targets))) = (picked.as_ref(), relations.as_ref()) else {
+            return;
+        };
+        if !Rc::ptr_eq(picked, source) {
+            return;
+        }
+        let from = center(&source.borrow().extents);
+        for (kind, target) in targets {
+            let to = center(&target.borrow().extents);
+            let (r, g, b) = match kind {
+                RelationType::LabelledBy | RelationType::LabelFor => (0.2, 0.4, 1.),
+                RelationType::ControllerFor | RelationType::ControlledBy => (0.1, 0.7, 0.2),
+                RelationType::FlowsTo | RelationType::FlowsFrom => (1., 0.5, 0.),
+                RelationType::MemberOf => (0.6, 0.2, 0.8),
+                RelationType::DescribedBy | RelationType::DescriptionFor => (0., 0.6, 0.6),
+                _ => (0.5, 0.5, 0.5),
+            };
+            let color = femtovg::Color::rgbaf(r, g, b, 0.9);
+            let mut stroke = femtovg::Paint::color(color);
+            stroke.set_line_width(2.);
+            let mut line = femtovg::Path::new();
+            line.move_to(from.0, from.1);
+            line.line_to(to.0, to.1);
+            canvas.stroke_path(&line, &stroke);
+            let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+            let len = (dx * dx + dy * dy).sqrt();
+            if len < 1. {
+                continue;
             }
+            let (ux, uy) = (dx / len, dy / len);
+            let mut head = femtovg::Path::new();
+            head.move_to(to.0, to.1);
+            head.line_to(to.0 - ux * 10. - uy * 5., to.1 - uy * 10. + ux * 5.);
+            head.line_to(to.0 - ux * 10. + uy * 5., to.1 - uy * 10. - ux * 5.);
+            head.close();
+            canvas.fill_path(&head, &femtovg::Paint::color(color));
+        }
+    }
+    fn draw_marks(&self, canvas: &mut Canvas) {
+        for mark in self.marks.borrow().values().flatten() {
+            let c = &mark.color;
+            let color = femtovg::Color::rgbaf(c.red(), c.green(), c.blue(), c.alpha());
+            let r = &mark.rect;
+            let mut path = femtovg::Path::new();
+            if r.width() > 0 && r.height() > 0 {
+                path.rect(
+                    r.x() as f32,
+                    r.y() as f32,
+                    r.width() as f32,
+                    r.height() as f32,
+                );
+                if mark.fill {
+                    let fill = femtovg::Color::rgbaf(c.red(), c.green(), c.blue(), c.alpha() * 0.3);
+                    canvas.fill_path(&path, &femtovg::Paint::color(fill));
+                }
+            } else {
+                // carets and other empty ranges become a vertical bar
+                let (x, y) = (r.x() as f32, r.y() as f32);
+                path.move_to(x, y);
+                path.line_to(x, y + r.height().max(8) as f32);
+            }
+            let mut stroke = femtovg::Paint::color(color);
+            stroke.set_line_width(2.);
+            canvas.stroke_path(&path, &stroke);
         }
-        false
     }
     fn scale(&self) -> f32 {
         let node = self.node.borrow();
         if let Some(node) = node.as_ref() {
+            let node = node.borrow();
             let overview = self.obj();
             let w = overview.width();
             let h = overview.height();
@@ -241,35 +634,37 @@ impl ObjectSubclass for OverviewImp {
 }
 
 impl ObjectImpl for OverviewImp {
+    fn signals() -> &'static [glib::subclass::Signal] {
+        static SIGNALS: glib::once_cell::sync::Lazy<Vec<glib::subclass::Signal>> =
+            glib::once_cell::sync::Lazy::new(|| {
+                vec![
+                    glib::subclass::Signal::builder("node-picked")
+                        .param_types([glib::BoxedAnyObject::static_type()])
+                        .build(),
+                    glib::subclass::Signal::builder("node-changed")
+                        .param_types([glib::BoxedAnyObject::static_type()])
+                        .build(),
+                    glib::subclass::Signal::builder("load-progress")
+                        .param_types([glib::BoxedAnyObject::static_type()])
+                        .build(),
+                ]
+            });
+        SIGNALS.as_ref()
+    }
     fn constructed(&self) {
         self.parent_constructed();
-        self.popover.popover.set_parent(&*self.obj());
         let click = gtk::GestureClick::new();
         click.connect_pressed(|ctrl, _, x, y| {
             let overview = ctrl.widget().downcast::<Overview>().unwrap();
-            let popover = &overview.imp().popover;
-            let set_labels = |node: &Node| {
-                popover.name.set_text(&node.name);
-                popover.role.set_text(node.role.name());
-                overview.imp().picked.set(Some(node.extents));
-            };
             let scale = overview.imp().scale() as f64;
             let sx = x / scale;
             let sy = y / scale;
-            if overview.imp().pick(sx as i32, sy as i32, set_labels) {
-                popover
-                    .popover
-                    .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
-                popover.popover.popup();
-            } else {
-                overview.imp().picked.set(None);
+            // empty canvas keeps the pick, so the sidebar never shows a node that isn't picked
+            if let Some(node) = overview.imp().pick(sx as i32, sy as i32) {
+                overview.imp().picked.replace(Some(node.clone()));
+                overview.emit_by_name::<()>("node-picked", &[&glib::BoxedAnyObject::new(node)]);
+                overview.queue_render();
             }
-            overview.queue_render();
-        });
-        self.popover.popover.connect_closed(|popover| {
-            let overview = popover.parent().unwrap().downcast::<Overview>().unwrap();
-            overview.imp().picked.set(None);
-            overview.queue_render();
         });
         self.obj().add_controller(click);
         let motion = gtk::EventControllerMotion::new();
@@ -278,15 +673,13 @@ impl ObjectImpl for OverviewImp {
             let scale = overview.imp().scale() as f64;
             let sx = x / scale;
             let sy = y / scale;
-            let mut hover_changed = false;
-            let set_hover = |node: &Node| {
-                let ext = Some(node.extents);
-                let old = overview.imp().hovered.replace(ext);
-                hover_changed = old != ext;
+            let node = overview.imp().pick(sx as i32, sy as i32);
+            let old = overview.imp().hovered.replace(node.clone());
+            let hover_changed = match (&old, &node) {
+                (Some(a), Some(b)) => !Rc::ptr_eq(a, b),
+                (None, None) => false,
+                _ => true,
             };
-            if !overview.imp().pick(sx as i32, sy as i32, set_hover) {
-                hover_changed = overview.imp().hovered.replace(None).is_some();
-            }
             if hover_changed {
                 overview.queue_render();
             }
@@ -330,36 +723,26 @@ impl GLAreaImpl for OverviewImp {
             ));
             fg.set_line_width(1.);
             canvas.scale(scale, scale);
-            self.draw(node, &mut *canvas, &fg);
-            if let Some(r) = self.picked.get() {
+            self.draw(&node.borrow(), &mut *canvas, &fg);
+            if let Some(node) = self.picked.borrow().as_ref() {
                 let sel = femtovg::Paint::color(femtovg::Color::rgbaf(1., 0., 0., 0.5));
-                let mut path = femtovg::Path::new();
-                path.rect(
-                    r.x() as f32,
-                    r.y() as f32,
-                    r.width() as f32,
-                    r.height() as f32,
-                );
-                canvas.fill_path(&path, &sel);
-            } else if let Some(r) = self.hovered.get() {
+                self.highlight(&node.borrow(), &mut *canvas, &sel);
+            } else if let Some(node) = self.hovered.borrow().as_ref() {
                 let sel = femtovg::Paint::color(femtovg::Color::rgbaf(0., 0., 1., 0.5));
-                let mut path = femtovg::Path::new();
-                path.rect(
-                    r.x() as f32,
-                    r.y() as f32,
-                    r.width() as f32,
-                    r.height() as f32,
-                );
-                canvas.fill_path(&path, &sel);
+                self.highlight(&node.borrow(), &mut *canvas, &sel);
             }
+            self.draw_relations(&mut *canvas);
+            self.draw_marks(&mut *canvas);
         }
 
         canvas.flush();
-
-        if self.handle.borrow().is_some() {
-            let overview = overview.clone();
-            glib::idle_add_local_once(move || overview.queue_render());
-        }
         true
     }
 }
+
+fn center(r: &gdk::Rectangle) -> (f32, f32) {
+    (
+        r.x() as f32 + r.width() as f32 / 2.,
+        r.y() as f32 + r.height() as f32 / 2.,
+    )
+}
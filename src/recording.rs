@@ -0,0 +1,109 @@
+use crate::{events::Event, monitor::Logged};
+use atspi::zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+use gtk::gdk;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufRead, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// One line of a JSON Lines recording.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    /// Seconds since the Unix epoch.
+    time: f64,
+    app: String,
+    source: String,
+    sender: String,
+    path: String,
+    class: String,
+    member: String,
+    kind: String,
+    detail1: i32,
+    detail2: i32,
+    any_data: String,
+    /// Window coordinates of the source when it was recorded, if it was loaded.
+    extents: Option<(i32, i32, i32, i32)>,
+}
+
+impl From<&Logged> for Record {
+    fn from(logged: &Logged) -> Self {
+        let event = &logged.event;
+        Self {
+            time: event
+                .time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+            app: logged.app.clone(),
+            source: logged.source.clone(),
+            sender: event.sender.clone(),
+            path: event.path.to_string(),
+            class: event.class.clone(),
+            member: event.member.clone(),
+            kind: event.kind.clone(),
+            detail1: event.detail1,
+            detail2: event.detail2,
+            any_data: event.any_data_text(),
+            extents: logged
+                .extents
+                .map(|r| (r.x(), r.y(), r.width(), r.height())),
+        }
+    }
+}
+
+impl TryFrom<Record> for Logged {
+    type Error = anyhow::Error;
+    fn try_from(record: Record) -> anyhow::Result<Self> {
+        Ok(Self {
+            event: Event {
+                class: record.class,
+                member: record.member,
+                kind: record.kind,
+                detail1: record.detail1,
+                detail2: record.detail2,
+                // only the text survives a recording
+                any_data: OwnedValue::from(Value::from(record.any_data)),
+                sender: record.sender,
+                path: OwnedObjectPath::try_from(record.path)?,
+                time: Duration::try_from_secs_f64(record.time)
+                    .ok()
+                    .and_then(|since| UNIX_EPOCH.checked_add(since))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid time {}", record.time))?,
+            },
+            app: record.app,
+            source: record.source,
+            extents: record
+                .extents
+                .map(|(x, y, w, h)| gdk::Rectangle::new(x, y, w, h)),
+        })
+    }
+}
+
+pub fn write(out: &mut impl Write, logged: &Logged) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut *out, &Record::from(logged))?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads a whole recording, failing on the first malformed line.
+pub fn read(input: impl BufRead) -> anyhow::Result<Vec<Logged>> {
+    let mut events = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let logged = serde_json::from_str::<Record>(&line)
+            .map_err(anyhow::Error::from)
+            .and_then(Logged::try_from)
+            .map_err(|err| anyhow::anyhow!("Line {}: {err}", index + 1))?;
+        events.push(logged);
+    }
+    Ok(events)
+}
+
+/// Time of `logged` relative to the first event of a recording.
+pub fn offset(first: SystemTime, logged: &Logged) -> Duration {
+    logged.event.time.duration_since(first).unwrap_or_default()
+}
@@ -0,0 +1,496 @@
+use crate::{events::Subscription, node::ObjectKey, overview::Overview, tree::row_label};
+use atspi::{accessible::AccessibleProxy, zbus};
+use futures_util::StreamExt;
+use gtk::{cairo, prelude::*};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// Event classes counted, registered the same way as the overview's live updates so the
+/// registry only sees each of them once.
+const CLASSES: &[&str] = &["object:", "window:", "focus:", "document:"];
+
+/// Seconds of history kept for the graphs and the noisiest sources.
+const WINDOW: usize = 60;
+
+/// Rows shown in each list.
+const TOP_N: usize = 10;
+
+const APP_ROOT: &str = "/org/a11y/atspi/accessible/root";
+
+/// Event counts for one second.
+#[derive(Default)]
+struct Bucket {
+    apps: HashMap<String, u32>,
+    kinds: HashMap<String, u32>,
+    sources: HashMap<ObjectKey, u32>,
+}
+
+/// Round trip of a D-Bus ping to an application, showing how far behind its bus queue is.
+#[derive(Default)]
+struct Ping {
+    in_flight: bool,
+    last: Option<Duration>,
+}
+
+/// Page counting events per application, event type and source object, to find what floods
+/// screen readers.
+#[derive(Clone)]
+pub struct StatsPage {
+    pub widget: gtk::Box,
+    overview: Overview,
+    /// Newest first; the front bucket is the second still being counted.
+    buckets: Rc<RefCell<VecDeque<Bucket>>>,
+    threshold: gtk::SpinButton,
+    warnings: gtk::Label,
+    apps: gtk::ListBox,
+    kinds: gtk::ListBox,
+    sources: gtk::ListBox,
+    /// Objects of the rows in `sources`, in order.
+    source_keys: Rc<RefCell<Vec<ObjectKey>>>,
+    names: Rc<RefCell<HashMap<String, String>>>,
+    pings: Rc<RefCell<HashMap<String, Ping>>>,
+    /// Rows over the threshold at the last update, so each crossing is only toasted once.
+    over: Rc<RefCell<HashSet<String>>>,
+    handle: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+}
+
+impl StatsPage {
+    pub fn new(conn: &zbus::Connection, overview: &Overview) -> Self {
+        let threshold = gtk::SpinButton::with_range(1., 1_000_000., 10.);
+        threshold.set_value(100.);
+        let page = Self {
+            widget: gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .build(),
+            overview: overview.clone(),
+            buckets: Default::default(),
+            threshold,
+            warnings: gtk::Label::builder()
+                .xalign(0.)
+                .wrap(true)
+                .visible(false)
+                .css_classes(["warning"])
+                .margin_start(6)
+                .margin_end(6)
+                .build(),
+            apps: list(),
+            kinds: list(),
+            sources: list(),
+            source_keys: Default::default(),
+            names: Default::default(),
+            pings: Default::default(),
+            over: Default::default(),
+            handle: Default::default(),
+        };
+        {
+            let page = page.clone();
+            page.sources.clone().connect_row_activated(move |_, row| {
+                let key = page.source_keys.borrow().get(row.index() as usize).cloned();
+                if let Some((dest, path)) = key {
+                    if !page.overview.pick_object(&dest, &path) {
+                        crate::toast(&page.widget, "Object is not loaded");
+                    }
+                }
+            });
+        }
+        {
+            let page = page.clone();
+            page.threshold
+                .clone()
+                .connect_value_changed(move |_| page.update());
+        }
+        {
+            let page = page.clone();
+            page.widget.clone().connect_map(move |_| page.update());
+        }
+
+        let collect = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        {
+            let page = page.clone();
+            let conn = conn.clone();
+            collect.connect_active_notify(move |collect| {
+                if let Some(handle) = page.handle.take() {
+                    handle.abort();
+                }
+                if collect.is_active() {
+                    page.buckets.borrow_mut().clear();
+                    page.over.borrow_mut().clear();
+                    let fut = page.clone().collect(conn.clone());
+                    page.handle
+                        .replace(Some(crate::spawn_fut(&page.widget, fut)));
+                }
+            });
+        }
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(6)
+            .margin_end(6)
+            .build();
+        toolbar.append(&gtk::Label::new(Some("Collect")));
+        toolbar.append(&collect);
+        toolbar.append(&gtk::Box::builder().hexpand(true).build());
+        toolbar.append(&gtk::Label::new(Some("Warn above")));
+        toolbar.append(&page.threshold);
+        toolbar.append(&gtk::Label::new(Some("events/s")));
+        page.widget.append(&toolbar);
+        page.widget.append(&page.warnings);
+
+        let columns = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(12)
+            .homogeneous(true)
+            .margin_start(6)
+            .margin_end(6)
+            .margin_bottom(6)
+            .build();
+        for (title, list) in [
+            ("Applications", &page.apps),
+            ("Event types", &page.kinds),
+            ("Noisiest sources", &page.sources),
+        ] {
+            let vbox = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(6)
+                .build();
+            vbox.append(
+                &gtk::Label::builder()
+                    .label(title)
+                    .xalign(0.)
+                    .css_classes(["heading"])
+                    .build(),
+            );
+            vbox.append(&gtk::Frame::builder().child(list).build());
+            columns.append(&vbox);
+        }
+        page.widget.append(
+            &gtk::ScrolledWindow::builder()
+                .child(&columns)
+                .vexpand(true)
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .build(),
+        );
+        page
+    }
+    async fn collect(self, conn: zbus::Connection) -> anyhow::Result<()> {
+        let _sub = Subscription::new(&conn, CLASSES).await?;
+        self.buckets.borrow_mut().push_front(Bucket::default());
+        let count = async {
+            let mut events = crate::events::stream(&conn);
+            while let Some(event) = events.next().await {
+                let mut buckets = self.buckets.borrow_mut();
+                let Some(bucket) = buckets.front_mut() else {
+                    continue;
+                };
+                *bucket.apps.entry(event.sender.clone()).or_default() += 1;
+                *bucket.kinds.entry(event.name()).or_default() += 1;
+                *bucket
+                    .sources
+                    .entry((event.sender, event.path))
+                    .or_default() += 1;
+            }
+        };
+        let tick = async {
+            loop {
+                glib::timeout_future(Duration::from_secs(1)).await;
+                {
+                    let mut buckets = self.buckets.borrow_mut();
+                    buckets.push_front(Bucket::default());
+                    buckets.truncate(WINDOW + 1);
+                }
+                // the lists are only rebuilt while someone can see them
+                if self.widget.is_mapped() {
+                    self.resolve(&conn);
+                    self.update();
+                }
+            }
+        };
+        futures_util::future::join(count, tick).await;
+        Ok(())
+    }
+    /// Looks up names and pings the applications seen in the last second.
+    fn resolve(&self, conn: &zbus::Connection) {
+        let buckets = self.buckets.borrow();
+        let Some(last) = buckets.get(1) else {
+            return;
+        };
+        for sender in last.apps.keys() {
+            if !self.names.borrow().contains_key(sender) {
+                self.names
+                    .borrow_mut()
+                    .insert(sender.clone(), sender.clone());
+                let names = self.names.clone();
+                let (conn, sender) = (conn.clone(), sender.clone());
+                glib::MainContext::default().spawn_local(async move {
+                    let res: zbus::Result<String> = async {
+                        AccessibleProxy::builder(&conn)
+                            .destination(sender.clone())?
+                            .path(APP_ROOT)?
+                            .build()
+                            .await?
+                            .name()
+                            .await
+                    }
+                    .await;
+                    match res {
+                        Ok(name) if !name.trim().is_empty() => {
+                            let name = format!("{} ({sender})", name.trim());
+                            names.borrow_mut().insert(sender, name);
+                        }
+                        Ok(_) => {}
+                        Err(err) => log::debug!("Name of {sender}: {err}"),
+                    }
+                });
+            }
+            let mut pings = self.pings.borrow_mut();
+            let ping = pings.entry(sender.clone()).or_default();
+            if ping.in_flight {
+                continue;
+            }
+            ping.in_flight = true;
+            let shared = self.pings.clone();
+            let (conn, sender) = (conn.clone(), sender.clone());
+            glib::MainContext::default().spawn_local(async move {
+                let start = Instant::now();
+                let res: zbus::Result<()> = async {
+                    zbus::fdo::PeerProxy::builder(&conn)
+                        .destination(sender.clone())?
+                        .path(APP_ROOT)?
+                        .build()
+                        .await?
+                        .ping()
+                        .await?;
+                    Ok(())
+                }
+                .await;
+                if let Err(err) = &res {
+                    log::debug!("Ping {sender}: {err}");
+                }
+                let mut pings = shared.borrow_mut();
+                let ping = pings.entry(sender).or_default();
+                ping.in_flight = false;
+                ping.last = res.ok().map(|_| start.elapsed());
+            });
+        }
+    }
+    /// Rebuilds the lists from the completed seconds.
+    fn update(&self) {
+        let threshold = self.threshold.value_as_int().max(1) as u32;
+        let buckets = self.buckets.borrow();
+        // oldest first, skipping the second still being counted
+        let complete = buckets.iter().skip(1).rev().collect::<Vec<_>>();
+        let mut warnings = Vec::new();
+
+        let names = self.names.borrow();
+        let pings = self.pings.borrow();
+        let apps = ranked(&complete, |b| &b.apps);
+        clear(&self.apps);
+        for (sender, series) in apps.iter().take(TOP_N) {
+            let name = names.get(sender).unwrap_or(sender);
+            let mut detail = rate_text(series);
+            if let Some(ping) = pings.get(sender) {
+                match ping.last {
+                    Some(time) => detail.push_str(&format!(" · ping {} ms", time.as_millis())),
+                    None if ping.in_flight => detail.push_str(" · ping pending"),
+                    None => {}
+                }
+            }
+            let over = current(series) > threshold;
+            if over {
+                warnings.push((sender.clone(), format!("{name}: {}/s", current(series))));
+            }
+            self.apps
+                .append(&rate_row(name, &detail, series.clone(), threshold, over));
+        }
+
+        let kinds = ranked(&complete, |b| &b.kinds);
+        clear(&self.kinds);
+        for (kind, series) in kinds.iter().take(TOP_N) {
+            let over = current(series) > threshold;
+            if over {
+                warnings.push((kind.clone(), format!("{kind}: {}/s", current(series))));
+            }
+            self.kinds.append(&rate_row(
+                kind,
+                &rate_text(series),
+                series.clone(),
+                threshold,
+                over,
+            ));
+        }
+
+        let sources = ranked(&complete, |b| &b.sources);
+        clear(&self.sources);
+        let mut keys = Vec::new();
+        for (key, series) in sources.into_iter().take(TOP_N) {
+            let label = match self.overview.find(&key.0, &key.1) {
+                Some(node) => row_label(&node.borrow()),
+                None => key.1.to_string(),
+            };
+            let app = names.get(&key.0).unwrap_or(&key.0);
+            let over = current(&series) > threshold;
+            if over {
+                warnings.push((
+                    format!("{} {}", key.0, key.1.as_str()),
+                    format!("{label} in {app}: {}/s", current(&series)),
+                ));
+            }
+            let total = series.iter().sum::<u32>();
+            let detail = format!("{app} · {total} in {}s", series.len());
+            self.sources
+                .append(&rate_row(&label, &detail, series, threshold, over));
+            keys.push(key);
+        }
+        self.source_keys.replace(keys);
+
+        let mut over = self.over.borrow_mut();
+        let crossed = warnings
+            .iter()
+            .filter(|(id, _)| !over.contains(id))
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>();
+        if !crossed.is_empty() {
+            crate::toast(
+                &self.widget,
+                &format!("Over {threshold} events/s: {}", crossed.join(", ")),
+            );
+        }
+        *over = warnings.iter().map(|(id, _)| id.clone()).collect();
+        self.warnings.set_visible(!warnings.is_empty());
+        self.warnings.set_label(&format!(
+            "Over {threshold} events/s: {}",
+            warnings
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+}
+
+fn list() -> gtk::ListBox {
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    list.set_placeholder(Some(
+        &gtk::Label::builder()
+            .label("No events")
+            .css_classes(["dim-label"])
+            .margin_top(12)
+            .margin_bottom(12)
+            .build(),
+    ));
+    list
+}
+
+fn clear(list: &gtk::ListBox) {
+    while let Some(row) = list.row_at_index(0) {
+        list.remove(&row);
+    }
+}
+
+/// Per-second counts for every key seen in `buckets`, noisiest over the whole window first.
+fn ranked<K: Clone + Eq + std::hash::Hash + Ord>(
+    buckets: &[&Bucket],
+    counts: fn(&Bucket) -> &HashMap<K, u32>,
+) -> Vec<(K, Vec<u32>)> {
+    let keys = buckets
+        .iter()
+        .flat_map(|b| counts(b).keys().cloned())
+        .collect::<HashSet<_>>();
+    let mut ranked = keys
+        .into_iter()
+        .map(|key| {
+            let series = buckets
+                .iter()
+                .map(|b| counts(b).get(&key).copied().unwrap_or(0))
+                .collect::<Vec<_>>();
+            (key, series)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(a, x), (b, y)| {
+        let (x, y) = (x.iter().sum::<u32>(), y.iter().sum::<u32>());
+        y.cmp(&x).then_with(|| a.cmp(b))
+    });
+    ranked
+}
+
+/// Events in the last completed second.
+fn current(series: &[u32]) -> u32 {
+    series.last().copied().unwrap_or(0)
+}
+
+fn rate_text(series: &[u32]) -> String {
+    let peak = series.iter().copied().max().unwrap_or(0);
+    format!("{}/s · peak {peak}/s", current(series))
+}
+
+fn rate_row(title: &str, detail: &str, series: Vec<u32>, threshold: u32, over: bool) -> gtk::Box {
+    let labels = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .hexpand(true)
+        .build();
+    let title = gtk::Label::builder()
+        .label(title)
+        .xalign(0.)
+        .ellipsize(gtk::pango::EllipsizeMode::Middle)
+        .build();
+    if over {
+        title.add_css_class("warning");
+    }
+    labels.append(&title);
+    labels.append(
+        &gtk::Label::builder()
+            .label(detail)
+            .xalign(0.)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .css_classes(["caption", "dim-label"])
+            .build(),
+    );
+    let row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .margin_top(4)
+        .margin_bottom(4)
+        .margin_start(6)
+        .margin_end(6)
+        .build();
+    row.append(&labels);
+    row.append(&sparkline(series, threshold));
+    row
+}
+
+/// Events per second over the window, with the threshold as a red line when it fits.
+fn sparkline(series: Vec<u32>, threshold: u32) -> gtk::DrawingArea {
+    let area = gtk::DrawingArea::builder()
+        .content_width(WINDOW as i32 * 2)
+        .content_height(24)
+        .valign(gtk::Align::Center)
+        .build();
+    area.set_draw_func(move |_, cr: &cairo::Context, w, h| {
+        let peak = series.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let (w, h) = (w as f64, h as f64);
+        let x = |i: usize| w - (series.len() - i) as f64 * w / WINDOW as f64;
+        let y = |count: f64| h - count / peak * (h - 2.) - 1.;
+        if (threshold as f64) <= peak {
+            cr.set_source_rgba(0.9, 0.1, 0.1, 0.6);
+            cr.set_line_width(1.);
+            cr.move_to(0., y(threshold as f64));
+            cr.line_to(w, y(threshold as f64));
+            let _ = cr.stroke();
+        }
+        cr.set_source_rgba(0.2, 0.5, 0.9, 1.);
+        cr.set_line_width(1.5);
+        for (i, &count) in series.iter().enumerate() {
+            cr.line_to(x(i), y(count as f64));
+        }
+        let _ = cr.stroke();
+    });
+    area
+}
@@ -0,0 +1,156 @@
+use crate::node::Node;
+use gtk::prelude::*;
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+type Bound = Rc<RefCell<Vec<(Weak<RefCell<Node>>, glib::WeakRef<gtk::Label>)>>>;
+
+#[derive(Clone)]
+pub struct Tree {
+    pub scroll: gtk::ScrolledWindow,
+    list: gtk::ListView,
+    model: gtk::TreeListModel,
+    select: gtk::SingleSelection,
+    /// Labels of the rows currently bound, so they can be updated in place.
+    bound: Bound,
+}
+
+impl Tree {
+    pub fn new(root: &gio::ListStore) -> Self {
+        let model = gtk::TreeListModel::new(root.clone(), false, false, |obj| {
+            let node = Node::from_item(obj);
+            let model = node.borrow().model.clone();
+            Some(model.upcast())
+        });
+        let select = gtk::SingleSelection::builder()
+            .model(&model)
+            .autoselect(false)
+            .can_unselect(true)
+            .build();
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_setup(|_, obj| {
+            let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+            let expander = gtk::TreeExpander::new();
+            expander.set_child(Some(
+                &gtk::Label::builder()
+                    .xalign(0.)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build(),
+            ));
+            item.set_child(Some(&expander));
+        });
+        let bound = Bound::default();
+        let b = bound.clone();
+        factory.connect_bind(move |_, obj| {
+            let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+            let row = item.item().unwrap().downcast::<gtk::TreeListRow>().unwrap();
+            let expander = item
+                .child()
+                .unwrap()
+                .downcast::<gtk::TreeExpander>()
+                .unwrap();
+            let label = expander.child().unwrap().downcast::<gtk::Label>().unwrap();
+            let node = Node::from_item(&row.item().unwrap());
+            update_label(&label, &node.borrow());
+            expander.set_list_row(Some(&row));
+            b.borrow_mut()
+                .push((Rc::downgrade(&node), label.downgrade()));
+        });
+        let b = bound.clone();
+        factory.connect_unbind(move |_, obj| {
+            let item = obj.downcast_ref::<gtk::ListItem>().unwrap();
+            let expander = item
+                .child()
+                .unwrap()
+                .downcast::<gtk::TreeExpander>()
+                .unwrap();
+            let label = expander.child().unwrap();
+            b.borrow_mut()
+                .retain(|(_, l)| l.upgrade().map_or(false, |l| l != label));
+            expander.set_list_row(None);
+        });
+        let list = gtk::ListView::new(Some(select.clone()), Some(factory));
+        let scroll = gtk::ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .width_request(200)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .build();
+        Self {
+            scroll,
+            list,
+            model,
+            select,
+            bound,
+        }
+    }
+    /// Updates the label of `node`'s row if it is on screen.
+    pub fn refresh(&self, node: &Rc<RefCell<Node>>) {
+        for (n, label) in self.bound.borrow().iter() {
+            if n.upgrade().map_or(false, |n| Rc::ptr_eq(&n, node)) {
+                if let Some(label) = label.upgrade() {
+                    update_label(&label, &node.borrow());
+                }
+            }
+        }
+    }
+    /// Expands the rows leading to `node`, then selects it and scrolls it into view.
+    pub fn select(&self, node: &Rc<RefCell<Node>>) {
+        let Some(root) = self.model.model().item(0) else {
+            return;
+        };
+        let Some(path) = Node::index_path(&Node::from_item(&root), node) else {
+            return;
+        };
+        let Some(mut row) = self.model.child_row(0) else {
+            return;
+        };
+        for index in path {
+            row.set_expanded(true);
+            match row.child_row(index) {
+                Some(child) => row = child,
+                None => return,
+            }
+        }
+        let pos = row.position();
+        self.select.set_selected(pos);
+        self.list
+            .activate_action("list.scroll-to-item", Some(&pos.to_variant()))
+            .ok();
+    }
+    pub fn connect_selected<F: Fn(Option<Rc<RefCell<Node>>>) + 'static>(&self, f: F) {
+        self.select.connect_selected_item_notify(move |select| {
+            let node = select.selected_item().map(|obj| {
+                let row = obj.downcast::<gtk::TreeListRow>().unwrap();
+                Node::from_item(&row.item().unwrap())
+            });
+            f(node);
+        });
+    }
+}
+
+fn update_label(label: &gtk::Label, node: &Node) {
+    label.set_label(&row_label(node));
+    if node.error.is_some() || node.is_unlabeled_image() {
+        label.add_css_class("warning");
+    } else {
+        label.remove_css_class("warning");
+    }
+}
+
+pub fn row_label(node: &Node) -> String {
+    let mut label = if node.name.is_empty() {
+        node.role.name().to_owned()
+    } else {
+        format!("{} “{}”", node.role.name(), node.name)
+    };
+    if node.is_unlabeled_image() {
+        label.push_str(" [unlabeled image]");
+    }
+    if let Some(truncated) = node.truncated {
+        label.push_str(&format!(" [{truncated}]"));
+    }
+    label
+}